@@ -0,0 +1,201 @@
+use crate::brain::Brain;
+use crate::constants::ENERGY_MEMORY_TICKS;
+use crate::traits::Traits;
+
+// Everything a newborn needs; the rest of its state starts from zero.
+pub struct Birth {
+    pub id: u64,
+    pub tick: u64,
+    pub position: (f64, f64),
+    pub angle: f64,
+    pub energy: f64,
+    pub brain: Brain,
+    pub traits: Traits,
+    pub tribe: usize,
+    pub species_id: u32,
+}
+
+// An offspring being carried by its mother, with the slot it will be born into
+// (None = appended as a new slot, in dynamic-population mode)
+pub struct Gestation {
+    pub slot: Option<usize>,
+    // (slot, id), to find him again at the birth
+    pub father: (usize, u64),
+    pub due: u64,
+    pub brain: Brain,
+    pub traits: Traits,
+}
+
+// Struct-of-arrays storage for all per-agent state. Slots only ever get (re)filled
+// through `spawn`, so a new field can't be forgotten on one of the birth paths.
+pub struct Agents {
+    pub positions: Vec<(f64, f64)>,
+    pub angles: Vec<f64>,
+    pub energies: Vec<f64>,
+    pub brains: Vec<Brain>,
+    pub traits: Vec<Traits>,
+    // Index into the palette, see TRIBE_COUNT
+    pub tribes: Vec<usize>,
+    pub voices: Vec<f64>,
+    // Stable, monotonically increasing per birth; indices get reused, ids never do
+    pub ids: Vec<u64>,
+    pub birth_ticks: Vec<u64>,
+    pub distances: Vec<f64>,
+    pub voice_totals: Vec<f64>,
+    pub offspring_counts: Vec<u32>,
+    pub kill_counts: Vec<u32>,
+    pub species_ids: Vec<u32>,
+    pub energy_history: Vec<[f64; ENERGY_MEMORY_TICKS]>,
+    pub last_meals: Vec<Option<(f64, f64)>>,
+    pub spawn_points: Vec<(f64, f64)>,
+    pub gestations: Vec<Option<Gestation>>,
+    pub parasite_loads: Vec<u32>,
+    // 0 = quenched, 1 = parched
+    pub thirsts: Vec<f64>,
+    // Eaten but not yet digested energy
+    pub stomachs: Vec<f64>,
+    // Stationary low-metabolism state, chosen by the brain each tick
+    pub torpid: Vec<bool>,
+    // Food item being carried, as its poison flag
+    pub carrying: Vec<Option<bool>>,
+    // Ticks spent at zero energy; death comes once this passes the grace period
+    pub starving_ticks: Vec<u32>,
+    // Tick from which the agent may parent offspring again
+    pub breed_ready: Vec<u64>,
+    // Underground (invisible to predators) until this tick
+    pub burrow_until: Vec<u64>,
+    // Tick from which the agent may burrow again
+    pub burrow_ready: Vec<u64>,
+    // Successful steals over this life
+    pub theft_counts: Vec<u32>,
+    // Metamorphosis stage: larvae live off mud and can't eat food items or fight
+    pub larval: Vec<bool>,
+    // 0..1; builds with movement and wears off while still (see update_fatigue)
+    pub fatigue: Vec<f64>,
+    // Predator that won't attack this cleaner, and the tick the truce ends
+    pub tolerated_by: Vec<Option<(usize, u64)>>,
+    // Not a living agent (dead, or reserved for an unborn offspring): skipped by the
+    // update loop, rendering and selection
+    pub vacant: Vec<bool>,
+    // Dead, unreserved slots up for reuse (dynamic-population mode only)
+    pub free_slots: Vec<usize>,
+}
+
+fn vec_bytes<T>(v: &Vec<T>) -> usize { v.capacity() * std::mem::size_of::<T>() }
+
+impl Agents {
+    pub fn new() -> Agents {
+        Agents {
+            positions: Vec::new(), angles: Vec::new(), energies: Vec::new(),
+            brains: Vec::new(), traits: Vec::new(), tribes: Vec::new(), voices: Vec::new(),
+            ids: Vec::new(), birth_ticks: Vec::new(), distances: Vec::new(), voice_totals: Vec::new(),
+            offspring_counts: Vec::new(), kill_counts: Vec::new(), species_ids: Vec::new(),
+            energy_history: Vec::new(), last_meals: Vec::new(), spawn_points: Vec::new(),
+            gestations: Vec::new(), parasite_loads: Vec::new(), thirsts: Vec::new(), stomachs: Vec::new(), torpid: Vec::new(), carrying: Vec::new(), starving_ticks: Vec::new(), breed_ready: Vec::new(), burrow_until: Vec::new(), burrow_ready: Vec::new(), theft_counts: Vec::new(), larval: Vec::new(), fatigue: Vec::new(), tolerated_by: Vec::new(), vacant: Vec::new(), free_slots: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize { self.positions.len() }
+
+    // Approximate heap use of the flat per-agent arrays; brain weights and energy history
+    // are reported separately by the memory report
+    pub fn state_bytes(&self) -> usize {
+        vec_bytes(&self.positions) + vec_bytes(&self.angles) + vec_bytes(&self.energies) + vec_bytes(&self.brains)
+            + vec_bytes(&self.traits) + vec_bytes(&self.tribes) + vec_bytes(&self.voices) + vec_bytes(&self.ids)
+            + vec_bytes(&self.birth_ticks) + vec_bytes(&self.distances) + vec_bytes(&self.voice_totals)
+            + vec_bytes(&self.offspring_counts) + vec_bytes(&self.kill_counts) + vec_bytes(&self.species_ids)
+            + vec_bytes(&self.last_meals) + vec_bytes(&self.spawn_points) + vec_bytes(&self.gestations)
+            + vec_bytes(&self.parasite_loads) + vec_bytes(&self.thirsts) + vec_bytes(&self.stomachs)
+            + vec_bytes(&self.torpid) + vec_bytes(&self.carrying) + vec_bytes(&self.starving_ticks)
+            + vec_bytes(&self.breed_ready) + vec_bytes(&self.vacant) + vec_bytes(&self.free_slots)
+            + vec_bytes(&self.burrow_until) + vec_bytes(&self.burrow_ready) + vec_bytes(&self.theft_counts) + vec_bytes(&self.larval) + vec_bytes(&self.fatigue) + vec_bytes(&self.tolerated_by)
+    }
+
+    pub fn history_bytes(&self) -> usize { vec_bytes(&self.energy_history) }
+    pub fn living(&self) -> usize { self.vacant.iter().filter(|v| !**v).count() }
+    pub fn living_ids(&self) -> Vec<u64> {
+        self.ids.iter().zip(&self.vacant).filter(|(_, v)| !**v).map(|(id, _)| *id).collect()
+    }
+
+    // Empties a slot for good (until a newborn claims it)
+    pub fn free(&mut self, i: usize) {
+        self.vacant[i] = true;
+        self.free_slots.push(i);
+    }
+
+    // The single birth path: fills `slot` (reusing a dead agent's index) or appends a new one
+    pub fn spawn(&mut self, slot: Option<usize>, birth: Birth) -> usize {
+        let i = match slot {
+            Some(i) => i,
+            None => { self.grow(birth.brain.clone(), birth.traits.clone()); self.len() - 1 }
+        };
+        self.positions[i] = birth.position;
+        self.angles[i] = birth.angle;
+        self.energies[i] = birth.energy;
+        self.brains[i] = birth.brain;
+        self.traits[i] = birth.traits;
+        self.tribes[i] = birth.tribe;
+        self.voices[i] = 0.0;
+        self.ids[i] = birth.id;
+        self.birth_ticks[i] = birth.tick;
+        self.distances[i] = 0.0;
+        self.voice_totals[i] = 0.0;
+        self.offspring_counts[i] = 0;
+        self.kill_counts[i] = 0;
+        self.species_ids[i] = birth.species_id;
+        self.energy_history[i] = [birth.energy; ENERGY_MEMORY_TICKS];
+        self.last_meals[i] = None;
+        self.spawn_points[i] = birth.position;
+        self.gestations[i] = None;
+        self.parasite_loads[i] = 0;
+        self.thirsts[i] = 0.0;
+        self.stomachs[i] = 0.0;
+        self.torpid[i] = false;
+        self.carrying[i] = None;
+        self.starving_ticks[i] = 0;
+        self.breed_ready[i] = birth.tick;
+        self.burrow_until[i] = 0;
+        self.burrow_ready[i] = 0;
+        self.theft_counts[i] = 0;
+        self.larval[i] = false;
+        self.fatigue[i] = 0.0;
+        self.tolerated_by[i] = None;
+        self.vacant[i] = false;
+        i
+    }
+
+    fn grow(&mut self, brain: Brain, traits: Traits) {
+        self.positions.push((0.0, 0.0));
+        self.angles.push(0.0);
+        self.energies.push(0.0);
+        self.brains.push(brain);
+        self.traits.push(traits);
+        self.tribes.push(0);
+        self.voices.push(0.0);
+        self.ids.push(0);
+        self.birth_ticks.push(0);
+        self.distances.push(0.0);
+        self.voice_totals.push(0.0);
+        self.offspring_counts.push(0);
+        self.kill_counts.push(0);
+        self.species_ids.push(0);
+        self.energy_history.push([0.0; ENERGY_MEMORY_TICKS]);
+        self.last_meals.push(None);
+        self.spawn_points.push((0.0, 0.0));
+        self.gestations.push(None);
+        self.parasite_loads.push(0);
+        self.thirsts.push(0.0);
+        self.stomachs.push(0.0);
+        self.torpid.push(false);
+        self.carrying.push(None);
+        self.starving_ticks.push(0);
+        self.breed_ready.push(0);
+        self.burrow_until.push(0);
+        self.burrow_ready.push(0);
+        self.theft_counts.push(0);
+        self.larval.push(false);
+        self.fatigue.push(0.0);
+        self.tolerated_by.push(None);
+        self.vacant.push(false);
+    }
+}
@@ -0,0 +1,127 @@
+use serde::Serialize;
+
+use crate::chunks::ChunkIndex;
+use crate::constants::{CHUNK_SIZE, WALL_THICKNESS};
+use crate::fixed::DetMath;
+use crate::geometry::{cross, point_segment_distance, point_segment_distance_sq, segment_distance};
+use crate::Simulation;
+
+// Straight-edged obstacle: two points make a wall segment WALL_THICKNESS wide, three or more a
+// convex polygon (stored counter-clockwise)
+#[derive(Clone, Serialize)]
+pub struct Barrier {
+    points: Vec<(f64, f64)>,
+    // Circle around the whole barrier (x, y, r), to skip the exact tests when far away
+    #[serde(skip)]
+    bounds: (f64, f64, f64),
+}
+
+impl Barrier {
+    fn new(points: Vec<(f64, f64)>) -> Barrier {
+        let n = points.len() as f64;
+        let (cx, cy) = (points.iter().map(|p| p.0).sum::<f64>() / n, points.iter().map(|p| p.1).sum::<f64>() / n);
+        let r = points.iter().map(|p| (p.0 - cx).dhypot(p.1 - cy)).fold(0.0, f64::max);
+        let padding = if points.len() == 2 { WALL_THICKNESS / 2.0 } else { 0.0 };
+        Barrier { points, bounds: (cx, cy, r + padding) }
+    }
+
+    pub fn wall(a: (f64, f64), b: (f64, f64)) -> Barrier { Barrier::new(vec![a, b]) }
+
+    // Flat [x0, y0, x1, y1, ...], as the JS API takes it
+    pub fn polygon_from_flat(flat: &[f64]) -> Result<Barrier, String> {
+        Barrier::polygon(flat.chunks_exact(2).map(|p| (p[0], p[1])).collect())
+    }
+
+    pub fn polygon(mut points: Vec<(f64, f64)>) -> Result<Barrier, String> {
+        if points.len() < 3 { return Err(format!("a polygon needs at least 3 points, got {}", points.len())); }
+        if points.iter().any(|p| !p.0.is_finite() || !p.1.is_finite()) { return Err("polygon points must be finite".into()); }
+        let turns: Vec<f64> = (0..points.len()).map(|k| cross(points[k], points[(k + 1) % points.len()], points[(k + 2) % points.len()])).collect();
+        if turns.iter().all(|&t| t <= 0.0) { points.reverse(); }
+        else if !turns.iter().all(|&t| t >= 0.0) { return Err("polygon must be convex".into()); }
+        if turns.iter().all(|&t| t == 0.0) { return Err("polygon has no area".into()); }
+        Ok(Barrier::new(points))
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] { &self.points }
+
+    // Same shape with every point moved by `f`
+    pub fn map_points(&self, f: impl FnMut(&mut (f64, f64))) -> Barrier {
+        let mut points = self.points.clone();
+        points.iter_mut().for_each(f);
+        Barrier::new(points)
+    }
+
+    pub fn is_wall(&self) -> bool { self.points.len() == 2 }
+
+    fn edges(&self) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+        let n = if self.is_wall() { 1 } else { self.points.len() };
+        (0..n).map(|k| (self.points[k], self.points[(k + 1) % self.points.len()]))
+    }
+
+    // How far outside the edges the barrier reaches
+    fn padding(&self) -> f64 { if self.is_wall() { WALL_THICKNESS / 2.0 } else { 0.0 } }
+
+    fn inside_polygon(&self, p: (f64, f64)) -> bool {
+        !self.is_wall() && self.edges().all(|(a, b)| cross(a, b, p) >= 0.0)
+    }
+
+    // Gap between a point and the barrier's surface; 0 inside
+    pub fn distance(&self, x: f64, y: f64) -> f64 {
+        if self.inside_polygon((x, y)) { return 0.0; }
+        let edge = self.edges().map(|(a, b)| point_segment_distance(x, y, a.0, a.1, b.0, b.1)).fold(f64::INFINITY, f64::min);
+        (edge - self.padding()).max(0.0)
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        let (cx, cy, r) = self.bounds;
+        if (x - cx) * (x - cx) + (y - cy) * (y - cy) >= r * r { return false; }
+        self.inside_polygon((x, y)) || self.edges().any(|(a, b)| point_segment_distance(x, y, a.0, a.1, b.0, b.1) < self.padding())
+    }
+
+    // Does the segment a -> b touch the barrier?
+    pub fn hits_segment(&self, a: (f64, f64), b: (f64, f64)) -> bool {
+        let (cx, cy, r) = self.bounds;
+        if point_segment_distance_sq(cx, cy, a.0, a.1, b.0, b.1) > r * r { return false; }
+        self.inside_polygon(a) || self.edges().any(|(c, d)| segment_distance(a, b, c, d) <= self.padding())
+    }
+
+    pub fn bounding_circle(&self) -> (f64, f64, f64) { self.bounds }
+}
+
+impl Simulation {
+    pub(crate) fn add_barrier(&mut self, barrier: Barrier) {
+        self.barriers.push(barrier);
+        self.reindex_barriers();
+        self.rebuild_nav();
+    }
+
+    pub(crate) fn remove_barriers_at(&mut self, x: f64, y: f64) {
+        self.barriers.retain(|b| !b.contains(x, y));
+        self.reindex_barriers();
+        self.rebuild_nav();
+    }
+
+    pub(crate) fn reindex_barriers(&mut self) {
+        self.barrier_chunks = ChunkIndex::new(self.width, self.height, CHUNK_SIZE);
+        for (k, b) in self.barriers.iter().enumerate() {
+            let (x, y, r) = b.bounding_circle();
+            self.barrier_chunks.insert_circle(x, y, r, k);
+        }
+    }
+
+    pub(crate) fn in_barrier(&self, x: f64, y: f64) -> bool {
+        self.barrier_chunks.at(x, y).iter().any(|&k| self.barriers[k].contains(x, y))
+    }
+
+    // Does any barrier cut the segment a -> b? A segment shorter than CHUNK_SIZE stays within the
+    // tiles around its midpoint's.
+    pub(crate) fn barrier_blocks(&self, a: (f64, f64), b: (f64, f64)) -> bool {
+        if self.barriers.is_empty() { return false; }
+        if (b.0 - a.0).dhypot(b.1 - a.1) < CHUNK_SIZE {
+            let (mx, my) = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+            self.barrier_chunks.ring(mx, my, 0).chain(self.barrier_chunks.ring(mx, my, 1)).any(|k| self.barriers[k].hits_segment(a, b))
+        } else {
+            self.barriers.iter().any(|barrier| barrier.hits_segment(a, b))
+        }
+    }
+}
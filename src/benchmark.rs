@@ -0,0 +1,88 @@
+use crate::rng::Math;
+
+use crate::brain::Brain;
+use crate::constants::*;
+use crate::geometry::random_circle;
+use crate::population::Genome;
+use crate::quality::Quality;
+use crate::resize::ResizeMode;
+use crate::rng;
+use crate::traits::Traits;
+use crate::Simulation;
+
+// A standard run everyone can compare scores on: same seed, world, terrain and starting
+// population, for BENCHMARK_TICKS ticks. Scores are food eaten plus agents alive.
+pub struct Benchmark {
+    pub start_tick: u64,
+    pub start_meals: u64,
+    // Score so far, every BENCHMARK_CHECKPOINT_TICKS ticks
+    pub checkpoints: Vec<f64>,
+    pub score: Option<f64>,
+    // Shared randomness from before the benchmark, handed back when it finishes
+    pub rng_before: Option<u64>,
+}
+
+impl Simulation {
+    // Rebuilds the world from BENCHMARK_SEED with a fresh random population. Settings (selection,
+    // brain options, food count and policy, ...) are left as they are, since they're what's being
+    // compared; the step-time budget is dropped so quality levels can't skew results.
+    pub(crate) fn start_benchmark_inner(&mut self) {
+        let rng_before = rng::state();
+        self.seed = BENCHMARK_SEED;
+        rng::reseed(Some(BENCHMARK_SEED));
+        self.quality = Quality::default();
+        let _ = self.resize_world_inner(BENCHMARK_SIZE, BENCHMARK_SIZE, ResizeMode::RescaleEntities);
+
+        let (w, h) = (self.width, self.height);
+        self.rocks = (0..ROCK_COUNT).map(|_| random_circle(w, h, ROCK_RADIUS)).collect();
+        self.mud = (0..MUD_COUNT).map(|_| random_circle(w, h, MUD_RADIUS)).collect();
+        self.water = (0..LAKE_COUNT).map(|_| random_circle(w, h, LAKE_RADIUS)).collect();
+        self.shelters = (0..SHELTER_COUNT).map(|_| random_circle(w, h, SHELTER_RADIUS)).collect();
+        self.barriers.clear();
+        self.clear_scenario();
+        self.reindex_rocks();
+        self.reindex_barriers();
+        self.rebuild_nav();
+
+        self.corpses.clear();
+        self.pending_food.clear();
+        let food = self.food.len();
+        self.truncate_food(0);
+        if !self.hotspots.is_empty() { self.reset_hotspots(self.hotspots.len()); }
+        self.set_food_count(food);
+        for p in 0..self.predators.len() {
+            self.predators[p] = self.random_open_position();
+            self.predator_homes[p] = self.predators[p];
+            self.predator_angles[p] = Math::random() * std::f64::consts::TAU;
+            self.predator_energies[p] = PREDATOR_STARTING_ENERGY;
+            self.predator_stamina[p] = self.stamina_max;
+            self.predator_resting[p] = false;
+        }
+        for p in 0..self.parasites.len() { self.parasites[p] = self.random_free_position(); }
+        self.scavengers = (0..self.scavengers.len()).map(|_| self.spawn_scavenger()).collect();
+
+        let genomes: Vec<Genome> = (0..AGENT_COUNT)
+            .map(|_| Genome { brain: Brain::new(), traits: Traits::random(), tribe: (Math::random() * TRIBE_COUNT as f64) as usize })
+            .collect();
+        self.reseed(&genomes, AGENT_COUNT, 0.0);
+
+        self.benchmark = Some(Benchmark { start_tick: self.tick, start_meals: self.meals, checkpoints: Vec::new(), score: None, rng_before });
+        self.log_buffer.push(format!("🏁 Benchmark started: {} ticks", BENCHMARK_TICKS));
+    }
+
+    pub(crate) fn update_benchmark(&mut self) {
+        let Some(b) = &self.benchmark else { return };
+        if b.score.is_some() { return; }
+        let elapsed = self.tick - b.start_tick;
+        let score = (self.meals - b.start_meals) as f64 + self.agents.living() as f64;
+        let done = elapsed >= BENCHMARK_TICKS;
+        let Some(b) = &mut self.benchmark else { return };
+        if elapsed.is_multiple_of(BENCHMARK_CHECKPOINT_TICKS) { b.checkpoints.push(score); }
+        if done {
+            b.score = Some(score);
+            self.log_buffer.push(format!("🏁 Benchmark finished: score {}", score));
+            // Back to the randomness the user had before
+            rng::restore(b.rng_before);
+        }
+    }
+}
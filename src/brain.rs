@@ -1,5 +1,8 @@
-use js_sys::Math;
-use serde::Serialize;
+use crate::rng::Math;
+use serde::{Deserialize, Serialize};
+use crate::constants::{BRAIN_INPUTS, BRAIN_HIDDEN, BRAIN_OUTPUTS, BRAIN_VERSION, MAX_IMPORT_LAYER};
+use crate::fixed::DetMath;
+use crate::crossover::{recombine, CrossoverStrategy};
 
 #[derive(Clone, Serialize)]
 pub struct Brain {
@@ -11,38 +14,64 @@ pub struct Brain {
     pub last_outputs: Vec<f64>,
 }
 
+// Export shape: the brain plus the version and topology it was built with
+#[derive(Serialize)]
+pub struct BrainExport<'a> {
+    version: u32,
+    inputs: usize,
+    hidden: usize,
+    outputs: usize,
+    #[serde(flatten)]
+    brain: &'a Brain,
+}
+
+// Import shape: anything a past or present export produced. Activations are ignored and
+// a missing version/topology means a legacy export, whose shape is inferred from the weights.
+#[derive(Deserialize)]
+pub struct BrainGenome {
+    #[serde(default = "legacy_version")]
+    version: u32,
+    inputs: Option<usize>,
+    hidden: Option<usize>,
+    outputs: Option<usize>,
+    weights_input: Vec<f64>,
+    weights_output: Vec<f64>,
+    biases: Vec<f64>,
+}
+
+fn legacy_version() -> u32 { 1 }
+
 impl Brain {
     pub fn new() -> Brain {
         let mut weights_input = Vec::new();
         let mut weights_output = Vec::new();
         let mut biases = Vec::new();
 
-        // CHANGED: 13 Inputs (Added Cosine for Food/Pred) * 8 Hidden
-        for _ in 0..(13 * 8) { weights_input.push((Math::random() * 2.0) - 1.0); } 
-        // 8 Hidden * 3 Outputs
-        for _ in 0..(8 * 3) { weights_output.push((Math::random() * 2.0) - 1.0); } 
-        // 8 Hidden + 3 Outputs
-        for _ in 0..(8 + 3) { biases.push((Math::random() * 2.0) - 1.0); }        
+        // Inputs * Hidden
+        for _ in 0..(BRAIN_INPUTS * BRAIN_HIDDEN) { weights_input.push((Math::random() * 2.0) - 1.0); } 
+        // Hidden * Outputs
+        for _ in 0..(BRAIN_HIDDEN * BRAIN_OUTPUTS) { weights_output.push((Math::random() * 2.0) - 1.0); } 
+        // Hidden + Outputs
+        for _ in 0..(BRAIN_HIDDEN + BRAIN_OUTPUTS) { biases.push((Math::random() * 2.0) - 1.0); }        
 
         Brain { 
             weights_input, weights_output, biases,
-            last_inputs: vec![0.0; 13], // Resized buffer
-            last_hidden: vec![0.0; 8],
-            last_outputs: vec![0.0; 3],
+            last_inputs: vec![0.0; BRAIN_INPUTS],
+            last_hidden: vec![0.0; BRAIN_HIDDEN],
+            last_outputs: vec![0.0; BRAIN_OUTPUTS],
         }
     }
 
-    pub fn crossover(&self, partner: &Brain) -> Brain {
-        let mix = |a: &Vec<f64>, b: &Vec<f64>| -> Vec<f64> {
-            a.iter().zip(b.iter()).map(|(&w1, &w2)| {
-                if Math::random() > 0.5 { w1 } else { w2 }
-            }).collect()
-        };
+    // Weights and biases are recombined as one flat genome, so cut points can fall anywhere
+    pub fn crossover(&self, partner: &Brain, strategy: CrossoverStrategy) -> Brain {
+        let flat = |b: &Brain| [b.weights_input.as_slice(), &b.weights_output, &b.biases].concat();
+        let genes = recombine(&flat(self), &flat(partner), strategy);
+        let (n_in, n_out) = (self.weights_input.len(), self.weights_output.len());
 
         let mut child = Brain::new();
-        child.weights_input = mix(&self.weights_input, &partner.weights_input);
-        child.weights_output = mix(&self.weights_output, &partner.weights_output);
-        child.biases = mix(&self.biases, &partner.biases);
+        child.weights_input = genes[..n_in].to_vec();
+        child.weights_output = genes[n_in..n_in + n_out].to_vec();
+        child.biases = genes[n_in + n_out..].to_vec();
         child
     }
 
@@ -65,28 +94,120 @@ impl Brain {
         child
     }
 
-    pub fn process(&mut self, inputs: &[f64]) -> Vec<f64> {
+    // Fixed-size in and out, so a sensor array that drifts from BRAIN_INPUTS fails to compile
+    pub fn process(&mut self, inputs: &[f64; BRAIN_INPUTS]) -> [f64; BRAIN_OUTPUTS] {
         self.last_inputs = inputs.to_vec();
 
-        let mut hidden = vec![0.0; 8];
-        for i in 0..8 {
+        let mut hidden = [0.0; BRAIN_HIDDEN];
+        for (i, h) in hidden.iter_mut().enumerate() {
             let mut sum = 0.0;
-            // CHANGED: Loop 13 times
-            for j in 0..13 { sum += inputs[j] * self.weights_input[i * 13 + j]; }
+            for (j, input) in inputs.iter().enumerate() { sum += input * self.weights_input[i * BRAIN_INPUTS + j]; }
             sum += self.biases[i];
-            hidden[i] = sum.tanh();
+            *h = sum.dtanh();
         }
-        self.last_hidden = hidden.clone();
+        self.last_hidden = hidden.to_vec();
 
-        let mut outputs = vec![0.0; 3];
-        for i in 0..3 {
+        let mut outputs = [0.0; BRAIN_OUTPUTS];
+        for (i, o) in outputs.iter_mut().enumerate() {
             let mut sum = 0.0;
-            for j in 0..8 { sum += hidden[j] * self.weights_output[i * 8 + j]; }
-            sum += self.biases[8 + i];
-            outputs[i] = sum.tanh();
+            for (j, h) in hidden.iter().enumerate() { sum += h * self.weights_output[i * BRAIN_HIDDEN + j]; }
+            sum += self.biases[BRAIN_HIDDEN + i];
+            *o = sum.dtanh();
         }
-        self.last_outputs = outputs.clone();
+        self.last_outputs = outputs.to_vec();
 
         outputs
     }
-}
\ No newline at end of file
+
+    // Zeroes connection weights (not biases) smaller than `threshold` in magnitude
+    pub fn prune(&mut self, threshold: f64) {
+        for w in self.weights_input.iter_mut().chain(self.weights_output.iter_mut()) {
+            if w.abs() < threshold { *w = 0.0; }
+        }
+    }
+
+    // Sparsity-biased mutation: each live connection is cut with probability `chance`
+    pub fn drop_connections(&mut self, chance: f64) {
+        for w in self.weights_input.iter_mut().chain(self.weights_output.iter_mut()) {
+            if *w != 0.0 && Math::random() < chance { *w = 0.0; }
+        }
+    }
+
+    // Share of connection weights that are exactly zero
+    pub fn sparsity(&self) -> f64 {
+        let total = self.weights_input.len() + self.weights_output.len();
+        let zeros = self.weights_input.iter().chain(&self.weights_output).filter(|w| **w == 0.0).count();
+        zeros as f64 / total.max(1) as f64
+    }
+
+    // Heap bytes of weights and cached activations
+    pub fn heap_bytes(&self) -> usize {
+        [&self.weights_input, &self.weights_output, &self.biases, &self.last_inputs, &self.last_hidden, &self.last_outputs]
+            .iter().map(|v| v.capacity() * std::mem::size_of::<f64>()).sum()
+    }
+
+    pub fn export(&self) -> BrainExport<'_> {
+        BrainExport { version: BRAIN_VERSION, inputs: BRAIN_INPUTS, hidden: BRAIN_HIDDEN, outputs: BRAIN_OUTPUTS, brain: self }
+    }
+
+    // Maps a genome of any past topology onto the current one. Sensors and outputs are only ever
+    // appended (the hunger input is the one exception, fixed up below), so overlapping indices keep
+    // their weights, dropped ones are truncated and new ones start at zero, leaving the imported
+    // behavior unchanged.
+    pub fn from_genome(g: BrainGenome) -> Result<Brain, String> {
+        if g.version > BRAIN_VERSION {
+            return Err(format!("brain version {} is newer than supported version {}", g.version, BRAIN_VERSION));
+        }
+        let hidden = g.hidden.unwrap_or(BRAIN_HIDDEN);
+        if hidden == 0 { return Err("brain has no hidden neurons".to_string()); }
+        let inputs = g.inputs.unwrap_or(g.weights_input.len() / hidden);
+        let outputs = g.outputs.unwrap_or(g.weights_output.len() / hidden);
+        let mut weights_input = g.weights_input;
+        // Exports before version 3 that have a hunger input put it at 26, ahead of the older
+        // carrying input at 27; swap them back (mismatched lengths are left for from_parts to reject)
+        if g.version <= 2 && inputs > 27 && inputs.checked_mul(hidden) == Some(weights_input.len()) {
+            weights_input.chunks_mut(inputs).for_each(|row| row.swap(26, 27));
+        }
+        Brain::from_parts((inputs, hidden, outputs), &weights_input, &g.weights_output, &g.biases)
+    }
+
+    // Input weight, output weight and bias counts of an inputs-hidden-outputs network, refusing
+    // topologies past MAX_IMPORT_LAYER so the products can't overflow
+    pub fn layer_sizes(shape: (usize, usize, usize)) -> Result<(usize, usize, usize), String> {
+        let (inputs, hidden, outputs) = shape;
+        if inputs.max(hidden).max(outputs) > MAX_IMPORT_LAYER {
+            return Err(format!("a {}-{}-{} brain is too large to import", inputs, hidden, outputs));
+        }
+        Ok((inputs * hidden, hidden * outputs, hidden + outputs))
+    }
+
+    // Same migration, from raw weights of an inputs-hidden-outputs network
+    pub fn from_parts(shape: (usize, usize, usize), weights_input: &[f64], weights_output: &[f64], biases: &[f64]) -> Result<Brain, String> {
+        let (inputs, hidden, outputs) = shape;
+        if (weights_input.len(), weights_output.len(), biases.len()) != Brain::layer_sizes(shape)? {
+            return Err(format!("weights don't match a {}-{}-{} brain", inputs, hidden, outputs));
+        }
+
+        let mut brain = Brain::new();
+        brain.weights_input.fill(0.0);
+        brain.weights_output.fill(0.0);
+        brain.biases.fill(0.0);
+        for h in 0..hidden.min(BRAIN_HIDDEN) {
+            for j in 0..inputs.min(BRAIN_INPUTS) { brain.weights_input[h * BRAIN_INPUTS + j] = weights_input[h * inputs + j]; }
+            brain.biases[h] = biases[h];
+        }
+        for o in 0..outputs.min(BRAIN_OUTPUTS) {
+            for h in 0..hidden.min(BRAIN_HIDDEN) { brain.weights_output[o * BRAIN_HIDDEN + h] = weights_output[o * hidden + h]; }
+            brain.biases[BRAIN_HIDDEN + o] = biases[hidden + o];
+        }
+        Ok(brain)
+    }
+
+    // Mean absolute weight difference, used as the genome compatibility distance
+    pub fn distance(&self, other: &Brain) -> f64 {
+        let a = self.weights_input.iter().chain(&self.weights_output).chain(&self.biases);
+        let b = other.weights_input.iter().chain(&other.weights_output).chain(&other.biases);
+        let (sum, count) = a.zip(b).fold((0.0, 0), |(s, n), (x, y)| (s + (x - y).abs(), n + 1));
+        if count == 0 { 0.0 } else { sum / count as f64 }
+    }
+}
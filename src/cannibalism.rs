@@ -0,0 +1,37 @@
+use crate::constants::*;
+use crate::death_map::DeathCause;
+use crate::events::EventKind;
+use crate::fixed::DetMath;
+use crate::Simulation;
+
+impl Simulation {
+    // Feeds off a corpse in reach, then preys on a touching rival far weaker than itself
+    pub(crate) fn cannibalize(&mut self, i: usize, neighbors: &[usize]) {
+        let (x, y) = self.agents.positions[i];
+        if let Some((idx, _)) = self.nearest_corpse(x, y).filter(|(_, d)| *d < self.eat_radius) {
+            let remaining = self.corpses.len();
+            let taken = self.eat_corpse(idx, CANNIBAL_BITE);
+            self.gain_energy(i, taken);
+            self.ledger.carrion += taken;
+            if self.corpses.len() < remaining { self.log_buffer.push(format!("🍖 Agent {} Picked a Corpse Clean", self.agents.ids[i])); }
+        }
+
+        let weak = self.agents.energies[i] * CANNIBAL_WEAKNESS_RATIO;
+        let victim = neighbors.iter().copied().find(|&j| {
+            let (vx, vy) = self.agents.positions[j];
+            j != i && !self.agents.vacant[j] && self.agents.tribes[j] != self.agents.tribes[i] && self.agents.energies[j] < weak
+                && !self.burrowed(j) && !self.in_shelter(vx, vy) && (vx - x).dhypot(vy - y) < CANNIBAL_ATTACK_RADIUS
+        });
+        let Some(j) = victim else { return };
+        let position = self.agents.positions[j];
+        self.gain_energy(i, CANNIBAL_KILL_ENERGY);
+        self.ledger.rewards += CANNIBAL_KILL_ENERGY;
+        self.agents.kill_counts[i] += 1;
+        self.kills += 1;
+        self.audio_kills += 1;
+        self.log_buffer.push(format!("🍖 Agent {} Devoured Agent {}", self.agents.ids[i], self.agents.ids[j]));
+        self.note_event(EventKind::Kill, position);
+        self.deaths.add(position.0, position.1, DeathCause::Predator);
+        self.die(j);
+    }
+}
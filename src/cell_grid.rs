@@ -0,0 +1,31 @@
+// Layout shared by the coarse per-cell maps (territory, fertility, trails, deaths): square
+// cells covering the world, indexed y * cols + x
+#[derive(Clone, Copy)]
+pub struct CellGrid {
+    pub cell_size: f64,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+impl CellGrid {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> CellGrid {
+        let cols = (width / cell_size).ceil().max(1.0) as usize;
+        let rows = (height / cell_size).ceil().max(1.0) as usize;
+        CellGrid { cell_size, cols, rows }
+    }
+
+    pub fn cell_count(&self) -> usize { self.cols * self.rows }
+
+    // World position of cell `c`'s top-left corner
+    pub fn corner(&self, c: usize) -> (f64, f64) {
+        ((c % self.cols) as f64 * self.cell_size, (c / self.cols) as f64 * self.cell_size)
+    }
+
+    // None outside the world
+    pub fn cell_at(&self, x: f64, y: f64) -> Option<usize> {
+        if x < 0.0 || y < 0.0 { return None; }
+        let col = (x / self.cell_size) as usize;
+        let row = (y / self.cell_size) as usize;
+        if col < self.cols && row < self.rows { Some(row * self.cols + col) } else { None }
+    }
+}
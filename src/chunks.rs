@@ -0,0 +1,70 @@
+// Coarse tiles listing which items touch each one, so lookups in big worlds only visit nearby
+// tiles instead of every rock or food item. Points outside the world count as the nearest
+// edge tile.
+pub struct ChunkIndex {
+    size: f64,
+    cols: usize,
+    rows: usize,
+    // Index = row * cols + col
+    tiles: Vec<Vec<usize>>,
+}
+
+impl ChunkIndex {
+    pub fn new(width: f64, height: f64, size: f64) -> ChunkIndex {
+        let cols = (width / size).ceil().max(1.0) as usize;
+        let rows = (height / size).ceil().max(1.0) as usize;
+        ChunkIndex { size, cols, rows, tiles: vec![Vec::new(); cols * rows] }
+    }
+
+    fn col_row(&self, x: f64, y: f64) -> (usize, usize) {
+        (((x / self.size).max(0.0) as usize).min(self.cols - 1), ((y / self.size).max(0.0) as usize).min(self.rows - 1))
+    }
+
+    fn tile(&self, x: f64, y: f64) -> usize {
+        let (c, r) = self.col_row(x, y);
+        r * self.cols + c
+    }
+
+    pub fn insert_point(&mut self, x: f64, y: f64, item: usize) {
+        let t = self.tile(x, y);
+        self.tiles[t].push(item);
+    }
+
+    // Registers a circle in every tile its bounding box touches
+    pub fn insert_circle(&mut self, x: f64, y: f64, r: f64, item: usize) {
+        let (c0, r0) = self.col_row(x - r, y - r);
+        let (c1, r1) = self.col_row(x + r, y + r);
+        for row in r0..=r1 {
+            for col in c0..=c1 { self.tiles[row * self.cols + col].push(item); }
+        }
+    }
+
+    pub fn remove_point(&mut self, x: f64, y: f64, item: usize) {
+        let t = self.tile(x, y);
+        self.tiles[t].retain(|&k| k != item);
+    }
+
+    // The item at (x, y) changed index from `from` to `to` (e.g. after a swap_remove)
+    pub fn relabel_point(&mut self, x: f64, y: f64, from: usize, to: usize) {
+        let t = self.tile(x, y);
+        if let Some(k) = self.tiles[t].iter_mut().find(|k| **k == from) { *k = to; }
+    }
+
+    pub fn at(&self, x: f64, y: f64) -> &[usize] { &self.tiles[self.tile(x, y)] }
+
+    // Items in the tiles exactly `k` tiles away (Chebyshev) from the one holding (x, y). Anything
+    // in ring k is at least (k - 1) * size away.
+    pub fn ring(&self, x: f64, y: f64, k: usize) -> impl Iterator<Item = usize> + '_ {
+        let (c, r) = self.col_row(x, y);
+        let (k, c, r) = (k as i64, c as i64, r as i64);
+        (r - k..=r + k).flat_map(move |row| (c - k..=c + k).map(move |col| (col, row)))
+            .filter(move |&(col, row)| (col - c).abs().max((row - r).abs()) == k)
+            .filter(|&(col, row)| col >= 0 && row >= 0 && (col as usize) < self.cols && (row as usize) < self.rows)
+            .flat_map(|(col, row)| self.tiles[row as usize * self.cols + col as usize].iter().copied())
+    }
+
+    // Rings beyond this hold nothing
+    pub fn max_ring(&self) -> usize { self.cols.max(self.rows) }
+
+    pub fn size(&self) -> f64 { self.size }
+}
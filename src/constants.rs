@@ -2,23 +2,277 @@
 pub const AGENT_COUNT: usize = 800;
 pub const FOOD_COUNT: usize = 100;
 pub const PREDATOR_COUNT: usize = 5;
+pub const SPAWN_ATTEMPTS: usize = 50;
+pub const SHELTER_COUNT: usize = 3;
+pub const LAKE_COUNT: usize = 3;
+pub const ROCK_COUNT: usize = 15;
+// Width of user-drawn wall segments
+pub const WALL_THICKNESS: f64 = 8.0;
+// Scenario layouts
+pub const MAZE_CELL_SIZE: f64 = 160.0;
+pub const CORRIDOR_WIDTH: f64 = 160.0;
+pub const ISLAND_COUNT: usize = 5;
+pub const ISLAND_RADIUS: f64 = 90.0;
+pub const ARENA_MARGIN: f64 = 40.0;
+pub const SCENARIO_LAKE_RADIUS: f64 = 25.0;
+pub const MUD_COUNT: usize = 10;
+// Tile size for the rock and food lookup indexes
+pub const CHUNK_SIZE: f64 = 500.0;
+// Terrain radii: (minimum, random extra)
+pub const ROCK_RADIUS: (f64, f64) = (20.0, 30.0);
+pub const MUD_RADIUS: (f64, f64) = (40.0, 60.0);
+pub const LAKE_RADIUS: (f64, f64) = (50.0, 40.0);
+pub const SHELTER_RADIUS: (f64, f64) = (40.0, 20.0);
+pub const TRIBE_COUNT: usize = 4;
+pub const TRIBE_COLORS: [&str; TRIBE_COUNT] = ["#ff00cc", "#ccff00", "#00ccff", "#ffcc00"];
+
+// Brain Topology
+pub const BRAIN_INPUTS: usize = 31;
+pub const BRAIN_HIDDEN: usize = 8;
+pub const BRAIN_OUTPUTS: usize = 8;
+// Largest layer an imported brain may have; anything bigger is a corrupt or hostile file
+pub const MAX_IMPORT_LAYER: usize = 1024;
+// Bumped whenever the exported genome layout changes; 1 = legacy exports without a version,
+// 3 = carrying and hunger inputs back in the order they were added
+pub const BRAIN_VERSION: u32 = 3;
+// Sensor groups an agent's genes can switch off: vision, hearing, whiskers, smell. Each lists the
+// brain inputs it feeds and costs its upkeep per tick while switched on.
+pub const SENSOR_GROUP_INPUTS: [&[usize]; 4] = [&[0, 1, 2, 3, 4, 5, 7, 28], &[11], &[8, 9, 10], &[21]];
+pub const SENSOR_GROUP_COSTS: [f64; 4] = [0.04, 0.01, 0.01, 0.01];
 
 // Physics
 pub const AGENT_SPEED_MODIFIER: f64 = 1.5;
 pub const TURN_SPEED: f64 = 0.2;
+pub const WIND_SPATIAL_SCALE: f64 = 0.01;
+pub const WIND_TIME_SCALE: f64 = 0.005;
+// Fixed-point mode grid: positions in 1/256 world units, energies in 1/1024
+pub const POSITION_SCALE: f64 = 256.0;
+pub const ENERGY_SCALE: f64 = 1024.0;
 
 // Energy / Metabolism
 pub const STARTING_ENERGY: f64 = 100.0;
 pub const FOOD_ENERGY: f64 = 40.0;
+pub const FOOD_RESPAWN_DELAY: u64 = 300;
+pub const POISON_DAMAGE: f64 = 30.0;
+pub const STOMACH_CAPACITY: f64 = 80.0;
+pub const DIGEST_RATE: f64 = 0.5;
+pub const FOOD_CLUSTER_SPREAD: f64 = 40.0;
+pub const HOTSPOT_SPREAD: f64 = 60.0;
+pub const HOTSPOT_DRIFT: f64 = 0.3;
 pub const ENERGY_CAP: f64 = 200.0;
+pub const ENERGY_HISTOGRAM_BINS: usize = 10;
 pub const MOVE_COST: f64 = 0.2;
 pub const WARRIOR_THRESHOLD: f64 = 150.0;
 pub const BATTLE_COST: f64 = 50.0;
+pub const COMBAT_UPKEEP: f64 = 0.02;
+pub const GESTATION_TICKS: u64 = 100;
+pub const GESTATION_SPEED_FACTOR: f64 = 0.7;
+pub const GESTATION_UPKEEP: f64 = 0.05;
+pub const JUVENILE_TICKS: u64 = 200;
+pub const JUVENILE_SPEED_FACTOR: f64 = 0.5;
+pub const PARENTAL_FEED_RATE: f64 = 0.5;
+pub const PARENTAL_FEED_MIN: f64 = 50.0;
+pub const THIRST_RATE: f64 = 0.001;
+pub const DRINK_RATE: f64 = 0.05;
+pub const THIRST_THRESHOLD: f64 = 0.7;
+pub const DEHYDRATION_COST: f64 = 1.0;
+pub const TORPOR_METABOLISM: f64 = 0.1;
+// Photosynthesis (energy per tick and speed lost at a gene value of 1)
+pub const PHOTOSYNTHESIS_RATE: f64 = 0.25;
+pub const PHOTOSYNTHESIS_SPEED_PENALTY: f64 = 0.6;
+pub const PHOTOSYNTHESIS_STILL_SPEED: f64 = 0.1;
+// Burrowing (only possible in mud)
+pub const BURROW_TICKS: u64 = 120;
+pub const BURROW_COOLDOWN: u64 = 300;
+pub const BURROW_COST: f64 = 0.15;
+// Metamorphosis (larvae turn adult at whichever threshold comes first)
+pub const METAMORPHOSIS_AGE: u64 = 600;
+pub const METAMORPHOSIS_ENERGY: f64 = 150.0;
+pub const LARVA_SPEED_FACTOR: f64 = 0.6;
+pub const LARVA_MUD_FEED: f64 = 0.4;
+// Fatigue (per unit of speed moved, and per tick spent still)
+pub const FATIGUE_RATE: f64 = 0.0004;
+pub const FATIGUE_RECOVERY: f64 = 0.004;
+pub const FATIGUE_REST_SPEED: f64 = 0.1;
+// Above this, brain outputs pick up noise growing to FATIGUE_NOISE at full exhaustion
+pub const FATIGUE_THRESHOLD: f64 = 0.6;
+pub const FATIGUE_NOISE: f64 = 0.8;
+pub const CARRY_SPEED_FACTOR: f64 = 0.7;
+pub const STARVING_THRESHOLD: f64 = 15.0;
+pub const STARVING_SPEED_FACTOR: f64 = 0.6;
+pub const STARVATION_GRACE: u32 = 60;
+pub const ENERGY_MEMORY_TICKS: usize = 30;
+
+// Predators
+pub const PREDATOR_STARTING_ENERGY: f64 = 100.0;
+pub const PREDATOR_KILL_ENERGY: f64 = 50.0;
+pub const PREDATOR_FIGHT_DAMAGE: f64 = 60.0;
+pub const FIGHT_KNOCKBACK: f64 = 40.0;
+pub const NAV_CELL_SIZE: f64 = 25.0;
+// Cells of detour a predator's route may take outside the box spanning it and its target
+pub const NAV_SEARCH_MARGIN: usize = 16;
+pub const PREDATOR_HUNT_RADIUS: f64 = 300.0;
+pub const PREDATOR_FOV: f64 = 2.6;
+pub const PREDATOR_VISION_RANGE: f64 = 400.0;
+pub const PREDATOR_SENSE_RADIUS: f64 = 25.0;
+pub const PREDATOR_STAMINA: f64 = 100.0;
+pub const STAMINA_DRAIN: f64 = 0.5;
+pub const STAMINA_RECOVERY: f64 = 1.0;
+pub const REST_SPEED_FACTOR: f64 = 0.3;
+// Past this fraction of its territory radius, a wandering predator heads back home
+pub const PATROL_TURNBACK: f64 = 0.8;
+
+// Carrion & Scavengers
+pub const CORPSE_ENERGY: f64 = 30.0;
+pub const CORPSE_DECAY: f64 = 0.02;
+pub const MAX_CORPSES: usize = 200;
+// Cannibalism: energy per tick taken from a corpse, and from killing a rival weaker than
+// CANNIBAL_WEAKNESS_RATIO of the attacker's energy
+pub const CANNIBAL_BITE: f64 = 2.0;
+pub const CANNIBAL_WEAKNESS_RATIO: f64 = 0.3;
+pub const CANNIBAL_ATTACK_RADIUS: f64 = 12.0;
+pub const CANNIBAL_KILL_ENERGY: f64 = 40.0;
+pub const SCAVENGER_COUNT: usize = 10;
+pub const SCAVENGER_STARTING_ENERGY: f64 = 100.0;
+pub const SCAVENGER_SPEED: f64 = 1.8;
+pub const SCAVENGER_UPKEEP: f64 = 0.05;
+pub const SCAVENGER_FLEE_RADIUS: f64 = 60.0;
+pub const SCAVENGER_KILL_ENERGY: f64 = 30.0;
 
 // Radiuses
 pub const EAT_RADIUS: f64 = 10.0;
 pub const PREDATOR_KILL_RADIUS: f64 = 15.0;
 pub const WHISKER_LEN: f64 = 50.0;
+pub const WHISKER_ANGLES: [f64; 3] = [-0.78, 0.0, 0.78];
+pub const HEARING_RADIUS: f64 = 100.0;
+pub const SMELL_RADIUS: f64 = 30.0;
+pub const FEED_RADIUS: f64 = 20.0;
+pub const HOVER_RADIUS: f64 = 12.0;
+pub const FLOCK_RADIUS: f64 = 50.0;
+pub const FLOCK_SEPARATION_RADIUS: f64 = 15.0;
+
+// Rendering
+pub const AGENT_SPRITE_SIZE: f64 = 14.0;
+pub const PREDATOR_SPRITE_SIZE: f64 = 24.0;
+pub const FOOD_SPRITE_SIZE: f64 = 10.0;
+// Screen pixels, whatever the zoom
+pub const MARKER_FONT_SIZE: f64 = 14.0;
+pub const MARKER_RADIUS: f64 = 6.0;
+// About three seconds at 60 ticks per second
+pub const GENE_FLOW_TICKS: u64 = 180;
+
+// Territory
+pub const TERRITORY_CELL_SIZE: f64 = 50.0;
+pub const TERRITORY_DEPOSIT: f64 = 0.02;
+pub const TERRITORY_DECAY: f64 = 0.995;
+pub const TERRITORY_MIN_STRENGTH: f64 = 0.2;
+pub const TERRITORY_STRESS: f64 = 0.05;
+
+// Death Heatmap
+pub const DEATH_MAP_CELL_SIZE: f64 = 50.0;
+pub const DEATH_MAP_DECAY: f32 = 0.999;
+
+// Parasites
+pub const PARASITE_COUNT: usize = 20;
+pub const PARASITE_DRIFT: f64 = 0.5;
+pub const PARASITE_LATCH_RADIUS: f64 = 8.0;
+pub const PARASITE_DRAIN: f64 = 0.03;
+pub const PARASITE_SPREAD_CHANCE: f64 = 0.01;
+pub const MAX_PARASITE_LOAD: u32 = 10;
+pub const GROOM_RADIUS: f64 = 15.0;
+pub const GROOM_COST: f64 = 0.05;
+// Cleaners: small agents grooming a predator
+pub const CLEANER_MAX_SIZE: f64 = 0.9;
+pub const CLEAN_RADIUS: f64 = 20.0;
+pub const CLEAN_ENERGY: f64 = 0.5;
+pub const CLEAN_PREDATOR_ENERGY: f64 = 0.2;
+pub const CLEAN_TRUCE_TICKS: u64 = 300;
+
+// Theft
+pub const STEAL_RADIUS: f64 = 12.0;
+pub const STEAL_AMOUNT: f64 = 2.0;
+pub const STEAL_EFFICIENCY: f64 = 0.7;
+pub const STEAL_COST: f64 = 0.05;
+// Scaled by the victim's combat gene
+pub const STEAL_RETALIATION_CHANCE: f64 = 0.3;
+pub const STEAL_RETALIATION_DAMAGE: f64 = 10.0;
+
+// Soil Fertility
+pub const FERTILITY_CELL_SIZE: f64 = 50.0;
+pub const FERTILITY_MAX: f64 = 10.0;
+pub const FERTILITY_DEATH: f64 = 1.0;
+pub const FERTILITY_DROPPING: f64 = 0.3;
+pub const FERTILITY_UPTAKE: f64 = 0.5;
+
+// Trails
+pub const TRAIL_CELL_SIZE: f64 = 20.0;
+pub const TRAIL_WEAR: f64 = 0.01;
+pub const TRAIL_DECAY: f64 = 0.998;
+pub const TRAIL_SPEED_BONUS: f64 = 0.3;
 
 // Evolution
-pub const BASE_MUTATION_RATE: f64 = 0.1;
\ No newline at end of file
+pub const BASE_MUTATION_RATE: f64 = 0.1;
+pub const TOURNAMENT_SIZE: usize = 5;
+// Past this a tournament just picks the fittest agent, and each pick costs up to 4x this many draws
+pub const MAX_TOURNAMENT_SIZE: usize = 256;
+pub const PARENT_COST: f64 = 20.0;
+pub const OFFSPRING_ENERGY: f64 = 60.0;
+pub const REPRODUCTION_COOLDOWN: u64 = 50;
+pub const CHAMPION_COUNT: usize = 10;
+pub const MATING_CHANCE: f64 = 0.01;
+pub const CARRYING_CAPACITY: usize = 1500;
+pub const DENSITY_COST: f64 = 0.01;
+pub const DENSITY_RADIUS: f64 = 30.0;
+
+// Novelty Search
+pub const NOVELTY_ARCHIVE_SIZE: usize = 250;
+pub const NOVELTY_ARCHIVE_CHANCE: f64 = 0.1;
+pub const NOVELTY_K: usize = 10;
+
+// Speciation
+pub const SPECIES_THRESHOLD: f64 = 0.5;
+pub const SPECIATION_INTERVAL: u64 = 100;
+
+// Lineage
+pub const LINEAGE_PRUNE_INTERVAL: u64 = 500;
+
+// Curriculum
+pub const CURRICULUM_INTERVAL: u64 = 500;
+pub const CURRICULUM_START_SPEED: f64 = 1.0;
+pub const CURRICULUM_START_COUNT: usize = 1;
+
+// Lock-step sessions
+pub const LOCKSTEP_INPUT_DELAY: u64 = 3;
+
+// Adaptive Quality
+pub const QUALITY_MAX: u32 = 3;
+pub const QUALITY_ADJUST_TICKS: u64 = 30;
+pub const QUALITY_SMOOTHING: f64 = 0.1;
+pub const DEGRADED_SENSOR_RANGE: f64 = 300.0;
+
+// Benchmark
+pub const BENCHMARK_SEED: u64 = 20240101;
+pub const BENCHMARK_SIZE: f64 = 2000.0;
+pub const BENCHMARK_TICKS: u64 = 50_000;
+pub const BENCHMARK_CHECKPOINT_TICKS: u64 = 5000;
+
+// Frame Timing: step_dt runs one tick per TICK_MS of wall time, at most MAX_SUBSTEPS per call
+pub const TICK_MS: f64 = 1000.0 / 60.0;
+pub const MAX_SUBSTEPS: u32 = 64;
+// catch_up covers at most a minute of missed time, and gives up after this much real work
+pub const CATCH_UP_MAX_TICKS: u32 = 3600;
+pub const CATCH_UP_BUDGET_MS: f64 = 1000.0;
+
+// Voice Spectrogram: per-tribe voice bands over the last VOICE_SPECTROGRAM_TICKS ticks
+pub const VOICE_SPECTROGRAM_TICKS: usize = 600;
+pub const VOICE_SPECTROGRAM_BINS: usize = 16;
+
+// Keyframes: one every KEYFRAME_INTERVAL ticks, the last KEYFRAME_CAPACITY kept
+pub const KEYFRAME_INTERVAL: u64 = 100;
+pub const KEYFRAME_CAPACITY: usize = 300;
+
+// Kill-cam: slow motion runs one tick every SLOW_MOTION_FACTOR steps
+pub const SLOW_MOTION_FACTOR: u32 = 4;
+
+// Rolling Statistics
+pub const STATS_HISTORY_TICKS: usize = 5000;
\ No newline at end of file
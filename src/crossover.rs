@@ -0,0 +1,33 @@
+use crate::rng::Math;
+use wasm_bindgen::prelude::*;
+
+// How two parent genomes are recombined into a child's
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum CrossoverStrategy {
+    Uniform,     // Each gene from a random parent (classic behavior)
+    SinglePoint, // Genes before a random cut from one parent, the rest from the other
+    TwoPoint,    // The span between two random cuts comes from the second parent
+    Blend,       // Every gene is the same random weighted average of both parents
+}
+
+// Recombines two equal-length gene sequences, treated as one contiguous genome
+pub fn recombine(a: &[f64], b: &[f64], strategy: CrossoverStrategy) -> Vec<f64> {
+    let cut = || (Math::random() * (a.len() + 1) as f64) as usize;
+    match strategy {
+        CrossoverStrategy::Uniform => a.iter().zip(b).map(|(&x, &y)| if Math::random() > 0.5 { x } else { y }).collect(),
+        CrossoverStrategy::SinglePoint => {
+            let c = cut();
+            a[..c].iter().chain(&b[c..]).copied().collect()
+        }
+        CrossoverStrategy::TwoPoint => {
+            let (c1, c2) = (cut(), cut());
+            let (lo, hi) = (c1.min(c2), c1.max(c2));
+            a[..lo].iter().chain(&b[lo..hi]).chain(&a[hi..]).copied().collect()
+        }
+        CrossoverStrategy::Blend => {
+            let t = Math::random();
+            a.iter().zip(b).map(|(&x, &y)| x * t + y * (1.0 - t)).collect()
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::constants::{CURRICULUM_INTERVAL, CURRICULUM_START_COUNT, CURRICULUM_START_SPEED};
+use crate::Simulation;
+
+// Predator ramp: every level adds `speed_step` speed and `count_step` predators,
+// unlocked each time the mean selection score clears `threshold`
+pub struct Curriculum {
+    pub threshold: f64,
+    pub speed_step: f64,
+    pub count_step: usize,
+    pub max_level: u32,
+    pub level: u32,
+    // Predator speed and count to restore once the curriculum is switched off
+    pub base: (f64, usize),
+}
+
+impl Simulation {
+    pub(crate) fn apply_difficulty(&mut self) {
+        let Some(c) = &self.curriculum else { return; };
+        self.predator_speed = CURRICULUM_START_SPEED + c.speed_step * c.level as f64;
+        let count = CURRICULUM_START_COUNT + c.count_step * c.level as usize;
+        self.resize_predators(count);
+    }
+
+    pub(crate) fn update_curriculum(&mut self) {
+        if !self.tick.is_multiple_of(CURRICULUM_INTERVAL) { return; }
+        let Some(c) = &self.curriculum else { return; };
+        if c.level >= c.max_level { return; }
+        let living: Vec<usize> = (0..self.agents.len()).filter(|&i| !self.agents.vacant[i]).collect();
+        if living.is_empty() { return; }
+        let mean = living.iter().map(|&i| self.selection_score(i)).sum::<f64>() / living.len() as f64;
+        if mean <= c.threshold { return; }
+        let level = c.level + 1;
+        if let Some(c) = &mut self.curriculum { c.level = level; }
+        self.apply_difficulty();
+        self.log_buffer.push(format!("📈 Difficulty raised to level {}", level));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::cell_grid::CellGrid;
+
+// Coarse grid of where agents died, per cause, fading over time so it shows current kill zones
+// and famine regions rather than the whole run's history
+pub struct DeathMap {
+    pub grid: CellGrid,
+    // Index = y * cols + x; [predator kills, starvation]
+    pub cells: Vec<[f32; 2]>,
+}
+
+#[derive(Clone, Copy)]
+pub enum DeathCause {
+    Predator,
+    Starvation,
+}
+
+impl DeathMap {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> DeathMap {
+        let grid = CellGrid::new(width, height, cell_size);
+        DeathMap { grid, cells: vec![[0.0; 2]; grid.cell_count()] }
+    }
+
+    pub fn add(&mut self, x: f64, y: f64, cause: DeathCause) {
+        if let Some(c) = self.grid.cell_at(x, y) { self.cells[c][cause as usize] += 1.0; }
+    }
+
+    pub fn decay(&mut self, factor: f32) {
+        for cell in &mut self.cells { for v in cell.iter_mut() { *v *= factor; } }
+    }
+
+    // Cell values, predator layer then starvation layer
+    pub fn flatten(&self) -> Vec<f32> {
+        (0..2).flat_map(|k| self.cells.iter().map(move |c| c[k])).collect()
+    }
+}
@@ -0,0 +1,26 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Simulation;
+
+// Dramatic moments the camera can jump to
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum EventKind {
+    Kill,  // An agent or a predator died in combat
+    Birth, // An offspring was born
+    Fight, // A warrior stood its ground against a predator
+}
+
+impl Simulation {
+    pub(crate) fn note_event(&mut self, kind: EventKind, position: (f64, f64)) {
+        self.last_events[kind as usize] = Some(position);
+    }
+
+    // Centers the camera on the latest event of `kind`; false if none has happened yet
+    pub(crate) fn focus_event(&mut self, kind: EventKind) -> bool {
+        let Some((x, y)) = self.last_events[kind as usize] else { return false; };
+        self.view_x = x - self.screen_w / self.zoom / 2.0;
+        self.view_y = y - self.screen_h / self.zoom / 2.0;
+        true
+    }
+}
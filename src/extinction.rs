@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+use crate::constants::{AGENT_COUNT, CHAMPION_COUNT, TRIBE_COUNT};
+use crate::population::Genome;
+use crate::Simulation;
+
+// Passed to the extinction callback. `tribe` is only set for tribe extinctions.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtinctionKind {
+    Tribe,      // A tribe lost its last member
+    Population, // Nobody left to breed: every recent slot went to a random newcomer (or nobody is alive)
+}
+
+#[derive(Serialize)]
+pub struct ExtinctionEvent {
+    kind: ExtinctionKind,
+    tribe: Option<usize>,
+    tick: u64,
+}
+
+impl Simulation {
+    // Keeps the CHAMPION_COUNT best-scoring genomes seen at death time
+    pub(crate) fn record_champion(&mut self, i: usize) {
+        let score = self.selection_score(i);
+        if self.champions.len() == CHAMPION_COUNT && self.champions.last().is_some_and(|(s, _)| score <= *s) { return; }
+        let genome = Genome { brain: self.agents.brains[i].clone(), traits: self.agents.traits[i].clone(), tribe: self.agents.tribes[i] };
+        let at = self.champions.iter().position(|(s, _)| score > *s).unwrap_or(self.champions.len());
+        self.champions.insert(at, (score, genome));
+        self.champions.truncate(CHAMPION_COUNT);
+    }
+
+    pub(crate) fn check_extinction(&mut self) {
+        let mut counts = [0; TRIBE_COUNT];
+        for i in 0..self.agents.len() {
+            if !self.agents.vacant[i] { counts[self.agents.tribes[i]] += 1; }
+        }
+        for (tribe, &count) in counts.iter().enumerate() {
+            let alive = count > 0;
+            if self.tribe_alive[tribe] && !alive {
+                self.log_buffer.push(format!("💀 Tribe {} went extinct", tribe));
+                self.emit_extinction(ExtinctionKind::Tribe, Some(tribe));
+            }
+            self.tribe_alive[tribe] = alive;
+        }
+
+        let gone = if self.dynamic_population {
+            counts.iter().sum::<usize>() == 0 && self.agents.gestations.iter().all(|g| g.is_none())
+        } else {
+            self.newcomer_streak >= self.agents.len()
+        };
+        // Reported once per extinction, not every tick it lasts
+        let newly_gone = gone && !self.population_extinct;
+        self.population_extinct = gone;
+        if !newly_gone { return; }
+        self.log_buffer.push(format!("☠️ Population extinct at tick {}", self.tick));
+        self.emit_extinction(ExtinctionKind::Population, None);
+        if self.auto_restart && !self.champions.is_empty() {
+            let champions: Vec<Genome> = self.champions.iter().map(|(_, g)| g.clone()).collect();
+            self.reseed(&champions, AGENT_COUNT, self.mutation_rate);
+            self.newcomer_streak = 0;
+            self.population_extinct = false;
+            self.log_buffer.push(format!("🔁 Restarted from {} champion genomes", champions.len()));
+        }
+    }
+
+    fn emit_extinction(&self, kind: ExtinctionKind, tribe: Option<usize>) {
+        let Some(callback) = &self.extinction_fn else { return; };
+        if let Ok(event) = serde_wasm_bindgen::to_value(&ExtinctionEvent { kind, tribe, tick: self.tick }) {
+            let _ = callback.call1(&wasm_bindgen::JsValue::NULL, &event);
+        }
+    }
+}
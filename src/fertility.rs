@@ -0,0 +1,38 @@
+use crate::rng::Math;
+
+use crate::cell_grid::CellGrid;
+use crate::constants::FERTILITY_MAX;
+
+// Coarse grid of soil nutrients. Every cell has a baseline weight of 1.0 for food spawns,
+// plus whatever deaths and droppings have added there.
+pub struct FertilityMap {
+    pub grid: CellGrid,
+    // Index = y * cols + x
+    pub cells: Vec<f64>,
+}
+
+impl FertilityMap {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> FertilityMap {
+        let grid = CellGrid::new(width, height, cell_size);
+        FertilityMap { grid, cells: vec![0.0; grid.cell_count()] }
+    }
+
+    pub fn add(&mut self, x: f64, y: f64, amount: f64) {
+        if let Some(c) = self.grid.cell_at(x, y) { self.cells[c] = (self.cells[c] + amount).clamp(0.0, FERTILITY_MAX); }
+    }
+
+    // Random point, with each cell's chance proportional to 1 + its fertility
+    pub fn sample(&self) -> (f64, f64) {
+        let total: f64 = self.cells.iter().map(|f| 1.0 + f).sum();
+        let mut r = Math::random() * total;
+        let mut cell = self.cells.len() - 1;
+        for (c, f) in self.cells.iter().enumerate() {
+            r -= 1.0 + f;
+            if r <= 0.0 { cell = c; break; }
+        }
+        (
+            ((cell % self.grid.cols) as f64 + Math::random()) * self.grid.cell_size,
+            ((cell / self.grid.cols) as f64 + Math::random()) * self.grid.cell_size,
+        )
+    }
+}
@@ -0,0 +1,53 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+// Weights applied to each objective when ranking parents in the tournament.
+// Terms are scaled to similar magnitudes: energy per 100, survival per 1000 ticks,
+// offspring and kills as raw counts.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct FitnessConfig {
+    pub energy: f64,
+    pub offspring: f64,
+    pub survival: f64,
+    pub kills: f64,
+}
+
+#[wasm_bindgen]
+impl FitnessConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new(energy: f64, offspring: f64, survival: f64, kills: f64) -> FitnessConfig {
+        FitnessConfig { energy, offspring, survival, kills }
+    }
+}
+
+impl FitnessConfig {
+    // Energy-only, i.e. the classic selection criterion
+    pub fn energy_only() -> FitnessConfig {
+        FitnessConfig { energy: 1.0, offspring: 0.0, survival: 0.0, kills: 0.0 }
+    }
+
+    pub fn score(&self, energy: f64, offspring: u32, age_ticks: u64, kills: u32) -> f64 {
+        self.energy * energy / 100.0
+            + self.offspring * offspring as f64
+            + self.survival * age_ticks as f64 / 1000.0
+            + self.kills * kills as f64
+    }
+}
+
+// Per-agent snapshot handed to a custom JS fitness function
+#[derive(Serialize)]
+pub struct AgentStats {
+    pub index: usize,
+    pub id: u64,
+    pub x: f64,
+    pub y: f64,
+    pub energy: f64,
+    pub age: u64,
+    pub offspring: u32,
+    pub kills: u32,
+    pub thefts: u32,
+    pub distance: f64,
+    pub tribe: usize,
+    pub color: String,
+}
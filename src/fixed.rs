@@ -0,0 +1,116 @@
+use std::cell::Cell;
+use std::f64::consts::{FRAC_PI_2, LN_2, PI, TAU};
+
+// Fixed-point mode: transcendental functions built only from + - * / and sqrt (which IEEE 754
+// requires to be correctly rounded), so every engine computes the same bits. Off by default,
+// since the platform versions are faster and slightly more accurate.
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn set_enabled(enabled: bool) { ENABLED.with(|e| e.set(enabled)); }
+
+fn enabled() -> bool { ENABLED.with(|e| e.get()) }
+
+// Rounds to the nearest 1/scale, the grid positions and energies live on in fixed-point mode
+pub fn snap(v: f64, scale: f64) -> f64 { (v * scale).round() / scale }
+
+// Drop-in replacements for the f64 methods the simulation uses; they defer to the platform
+// unless fixed-point mode is on
+pub trait DetMath {
+    fn dsin(self) -> f64;
+    fn dcos(self) -> f64;
+    fn datan2(self, x: f64) -> f64;
+    fn dtanh(self) -> f64;
+    fn dhypot(self, other: f64) -> f64;
+    fn dln(self) -> f64;
+}
+
+impl DetMath for f64 {
+    fn dsin(self) -> f64 { if enabled() { sin(self) } else { self.sin() } }
+    fn dcos(self) -> f64 { if enabled() { sin(self + FRAC_PI_2) } else { self.cos() } }
+    fn datan2(self, x: f64) -> f64 { if enabled() { atan2(self, x) } else { self.atan2(x) } }
+    fn dtanh(self) -> f64 { if enabled() { tanh(self) } else { self.tanh() } }
+    fn dhypot(self, other: f64) -> f64 { if enabled() { (self * self + other * other).sqrt() } else { self.hypot(other) } }
+    fn dln(self) -> f64 { if enabled() { ln(self) } else { self.ln() } }
+}
+
+// Reduce to [-pi/2, pi/2] by symmetry, then a Taylor series to x^15 (error below 1e-12)
+fn sin(x: f64) -> f64 {
+    if !x.is_finite() { return f64::NAN; }
+    let mut x = x - TAU * (x / TAU).round();
+    if x > FRAC_PI_2 { x = PI - x; } else if x < -FRAC_PI_2 { x = -PI - x; }
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    for n in 1..8 {
+        term *= -x2 / ((2 * n) as f64 * (2 * n + 1) as f64);
+        sum += term;
+    }
+    sum
+}
+
+// Halve the argument twice (|z| <= tan(pi/8) afterwards) and sum the series to z^25
+fn atan(z: f64) -> f64 {
+    let (z, flip) = if z.abs() > 1.0 { (1.0 / z, true) } else { (z, false) };
+    let mut r = z;
+    for _ in 0..2 { r /= 1.0 + (1.0 + r * r).sqrt(); }
+    let r2 = r * r;
+    let mut power = r;
+    let mut sum = r;
+    for n in 1..13 {
+        power *= -r2;
+        sum += power / (2 * n + 1) as f64;
+    }
+    let a = sum * 4.0;
+    if flip { FRAC_PI_2.copysign(z) - a } else { a }
+}
+
+fn atan2(y: f64, x: f64) -> f64 {
+    if x > 0.0 { atan(y / x) }
+    else if x < 0.0 { atan(y / x) + PI.copysign(y) }
+    else if y != 0.0 { FRAC_PI_2.copysign(y) }
+    else { 0.0 }
+}
+
+// e^x = 2^k * e^r with |r| <= ln2/2; 2^k is built directly from the exponent bits
+fn exp(x: f64) -> f64 {
+    if x > 700.0 { return f64::INFINITY; }
+    if x < -700.0 { return 0.0; }
+    let k = (x / LN_2).round();
+    let r = x - k * LN_2;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..14 {
+        term *= r / n as f64;
+        sum += term;
+    }
+    sum * f64::from_bits(((k as i64 + 1023) as u64) << 52)
+}
+
+fn tanh(x: f64) -> f64 {
+    if x > 20.0 { return 1.0; }
+    if x < -20.0 { return -1.0; }
+    let e = exp(2.0 * x);
+    (e - 1.0) / (e + 1.0)
+}
+
+// ln x = k ln2 + ln m with m in [1, 2), via ln m = 2 atanh((m - 1) / (m + 1))
+fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 { return f64::NAN; }
+    if x == 0.0 { return f64::NEG_INFINITY; }
+    if x.is_infinite() { return x; }
+    let (x, shift) = if x < f64::MIN_POSITIVE { (x * (1u64 << 54) as f64, -54) } else { (x, 0) };
+    let bits = x.to_bits();
+    let k = ((bits >> 52) & 0x7ff) as i64 - 1023 + shift;
+    let m = f64::from_bits((bits & ((1u64 << 52) - 1)) | (1023u64 << 52));
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let mut power = s;
+    let mut sum = s;
+    for n in 1..20 {
+        power *= s2;
+        sum += power / (2 * n + 1) as f64;
+    }
+    k as f64 * LN_2 + 2.0 * sum
+}
@@ -0,0 +1,33 @@
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::Simulation;
+
+impl Simulation {
+    // Classic boids steering among same-tribe neighbors, in world units per tick.
+    // Zero when all three strengths are zero.
+    pub(crate) fn flocking_force(&self, i: usize, neighbors: &[usize]) -> (f64, f64) {
+        let (sep_w, align_w, coh_w) = self.flocking;
+        if sep_w == 0.0 && align_w == 0.0 && coh_w == 0.0 { return (0.0, 0.0); }
+        let (x, y) = self.agents.positions[i];
+        let (mut sep, mut align, mut center, mut n) = ((0.0, 0.0), (0.0, 0.0), (0.0, 0.0), 0.0);
+        for &j in neighbors {
+            if j == i || self.agents.tribes[j] != self.agents.tribes[i] { continue; }
+            let (ox, oy) = self.agents.positions[j];
+            let d = (ox - x).dhypot(oy - y);
+            if d >= FLOCK_RADIUS || d == 0.0 { continue; }
+            if d < FLOCK_SEPARATION_RADIUS { sep.0 += (x - ox) / d; sep.1 += (y - oy) / d; }
+            align.0 += self.agents.angles[j].dcos(); align.1 += self.agents.angles[j].dsin();
+            center.0 += ox; center.1 += oy;
+            n += 1.0;
+        }
+        if n == 0.0 { return (0.0, 0.0); }
+        let unit = |v: (f64, f64)| { let l = v.0.dhypot(v.1); if l > 0.0 { (v.0 / l, v.1 / l) } else { (0.0, 0.0) } };
+        let sep = unit(sep);
+        let align = unit(align);
+        let coh = unit((center.0 / n - x, center.1 / n - y));
+        (
+            sep_w * sep.0 + align_w * align.0 + coh_w * coh.0,
+            sep_w * sep.1 + align_w * align.1 + coh_w * coh.1,
+        )
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use crate::constants::NAV_SEARCH_MARGIN;
+use crate::fixed::DetMath;
+
+// Coarse navigation grid: cells overlapping rocks are blocked, and a BFS from the
+// target cell gives every reachable cell its step distance to the target. The BFS only
+// covers the box around both ends (plus NAV_SEARCH_MARGIN cells), so its cost follows the
+// length of the route, not the size of the world; a route that has to detour further than
+// that isn't found, and the predator heads straight for its target instead.
+pub struct FlowField {
+    cell_size: f64,
+    cols: usize,
+    rows: usize,
+    blocked: Vec<bool>,
+    dist: Vec<u32>,
+    // Cells the last search gave a distance, reset before the next one
+    visited: Vec<usize>,
+}
+
+impl FlowField {
+    pub fn new(width: f64, height: f64, cell_size: f64, rocks: &[(f64, f64, f64)]) -> FlowField {
+        let cols = (width / cell_size).ceil().max(1.0) as usize;
+        let rows = (height / cell_size).ceil().max(1.0) as usize;
+        let mut field = FlowField { cell_size, cols, rows, blocked: vec![false; cols * rows], dist: vec![u32::MAX; cols * rows], visited: Vec::new() };
+        // Only the cells under each rock's bounding box can touch it
+        for (rx, ry, rr) in rocks {
+            let reach = rr + cell_size * 0.5;
+            let (c0, r0) = field.cell_of(rx - reach, ry - reach);
+            let (c1, r1) = field.cell_of(rx + reach, ry + reach);
+            for r in r0..=r1 {
+                for c in c0..=c1 {
+                    let (x, y) = field.center(c, r);
+                    if (x - rx).dhypot(y - ry) < reach { field.blocked[r * cols + c] = true; }
+                }
+            }
+        }
+        field
+    }
+
+    // Also blocks every cell whose center is within half a cell of something `distance` measures
+    pub fn block_near(&mut self, distance: impl Fn(f64, f64) -> f64) {
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let (x, y) = self.center(c, r);
+                if distance(x, y) < self.cell_size * 0.5 { self.blocked[r * self.cols + c] = true; }
+            }
+        }
+    }
+
+    fn cell_of(&self, x: f64, y: f64) -> (usize, usize) {
+        let c = ((x / self.cell_size).floor().max(0.0) as usize).min(self.cols - 1);
+        let r = ((y / self.cell_size).floor().max(0.0) as usize).min(self.rows - 1);
+        (c, r)
+    }
+
+    fn center(&self, c: usize, r: usize) -> (f64, f64) {
+        ((c as f64 + 0.5) * self.cell_size, (r as f64 + 0.5) * self.cell_size)
+    }
+
+    fn passable(&self, c: i64, r: i64) -> bool {
+        c >= 0 && r >= 0 && (c as usize) < self.cols && (r as usize) < self.rows && !self.blocked[r as usize * self.cols + c as usize]
+    }
+
+    // Next waypoint (a neighbouring cell center) on the shortest route from `from` to `to`
+    pub fn next_waypoint(&mut self, from: (f64, f64), to: (f64, f64)) -> Option<(f64, f64)> {
+        let (tc, tr) = self.cell_of(to.0, to.1);
+        let (fc, fr) = self.cell_of(from.0, from.1);
+        if (tc, tr) == (fc, fr) { return Some(to); }
+
+        for &idx in &self.visited { self.dist[idx] = u32::MAX; }
+        self.visited.clear();
+        let (c0, r0) = (fc.min(tc).saturating_sub(NAV_SEARCH_MARGIN) as i64, fr.min(tr).saturating_sub(NAV_SEARCH_MARGIN) as i64);
+        let (c1, r1) = ((fc.max(tc) + NAV_SEARCH_MARGIN) as i64, (fr.max(tr) + NAV_SEARCH_MARGIN) as i64);
+        let mut queue = VecDeque::new();
+        self.dist[tr * self.cols + tc] = 0;
+        self.visited.push(tr * self.cols + tc);
+        queue.push_back((tc as i64, tr as i64));
+        while let Some((c, r)) = queue.pop_front() {
+            if (c as usize, r as usize) == (fc, fr) { break; }
+            let d = self.dist[r as usize * self.cols + c as usize];
+            let next: Vec<(i64, i64)> = self.neighbors(c, r).collect();
+            for (nc, nr) in next {
+                if nc < c0 || nc > c1 || nr < r0 || nr > r1 { continue; }
+                let idx = nr as usize * self.cols + nc as usize;
+                if self.dist[idx] == u32::MAX {
+                    self.dist[idx] = d + 1;
+                    self.visited.push(idx);
+                    queue.push_back((nc, nr));
+                }
+            }
+        }
+
+        self.neighbors(fc as i64, fr as i64)
+            .filter(|&(c, r)| self.dist[r as usize * self.cols + c as usize] != u32::MAX)
+            .min_by_key(|&(c, r)| self.dist[r as usize * self.cols + c as usize])
+            .map(|(c, r)| self.center(c as usize, r as usize))
+    }
+
+    // 8-connected, without cutting corners past blocked cells
+    fn neighbors(&self, c: i64, r: i64) -> impl Iterator<Item = (i64, i64)> + '_ {
+        [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)].into_iter().filter_map(move |(dc, dr)| {
+            let (nc, nr) = (c + dc, r + dr);
+            if !self.passable(nc, nr) { return None; }
+            if dc != 0 && dr != 0 && (!self.passable(c + dc, r) || !self.passable(c, r + dr)) { return None; }
+            Some((nc, nr))
+        })
+    }
+}
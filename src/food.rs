@@ -0,0 +1,165 @@
+use wasm_bindgen::prelude::*;
+use crate::rng::Math;
+
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::rng;
+use crate::Simulation;
+
+// What happens to a food item once it has been eaten
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum FoodRespawnPolicy {
+    Instant,      // Reappears immediately somewhere random (classic behavior)
+    Delayed,      // Reappears after `food_respawn_delay` ticks
+    NearExisting, // Reappears next to another surviving food item
+    Finite,       // Gone for good: pure scarcity
+}
+
+// Where new food appears
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum FoodDistribution {
+    Uniform, // Anywhere on the map
+    Patchy,  // Gaussian clusters around slowly drifting hotspots
+}
+
+pub struct Hotspot {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+}
+
+impl Simulation {
+    // Where a freshly spawned food item goes
+    pub(crate) fn new_food_position(&self) -> (f64, f64) {
+        // A scenario confines food to its zones
+        if !self.food_zones.is_empty() {
+            let zone = self.food_zones[(Math::random() * self.food_zones.len() as f64) as usize];
+            if let Some(pos) = self.free_position_in(zone).filter(|&(x, y)| !self.in_shelter(x, y)) { return pos; }
+        }
+        if self.food_distribution == FoodDistribution::Patchy && !self.hotspots.is_empty() {
+            let h = &self.hotspots[(Math::random() * self.hotspots.len() as f64) as usize];
+            if let Some(pos) = self.free_position_near(h.x, h.y, HOTSPOT_SPREAD) { return pos; }
+        }
+        // Otherwise rich soil is more likely to sprout
+        for _ in 0..SPAWN_ATTEMPTS {
+            let (x, y) = self.fertility.sample();
+            if self.is_free(x, y) && !self.in_shelter(x, y) { return (x, y); }
+        }
+        self.random_open_position()
+    }
+
+    pub(crate) fn reset_hotspots(&mut self, clusters: usize) {
+        self.hotspots = (0..clusters).map(|_| {
+            let (x, y) = self.random_free_position();
+            let heading = Math::random() * std::f64::consts::TAU;
+            Hotspot { x, y, vx: heading.dcos() * HOTSPOT_DRIFT, vy: heading.dsin() * HOTSPOT_DRIFT }
+        }).collect();
+    }
+
+    // Hotspots wander slowly and bounce off the world edges, so patches migrate
+    pub(crate) fn update_hotspots(&mut self) {
+        for h in &mut self.hotspots {
+            let heading = h.vy.datan2(h.vx) + (Math::random() - 0.5) * 0.1;
+            h.vx = heading.dcos() * HOTSPOT_DRIFT;
+            h.vy = heading.dsin() * HOTSPOT_DRIFT;
+            h.x += h.vx; h.y += h.vy;
+            if h.x < 0.0 || h.x > self.width { h.vx = -h.vx; h.x = h.x.clamp(0.0, self.width); }
+            if h.y < 0.0 || h.y > self.height { h.vy = -h.vy; h.y = h.y.clamp(0.0, self.height); }
+        }
+    }
+
+    fn free_position_near(&self, cx: f64, cy: f64, spread: f64) -> Option<(f64, f64)> {
+        for _ in 0..SPAWN_ATTEMPTS {
+            let x = cx + rng::gaussian() * spread;
+            let y = cy + rng::gaussian() * spread;
+            if x >= 0.0 && x <= self.width && y >= 0.0 && y <= self.height && self.is_free(x, y) && !self.in_shelter(x, y) { return Some((x, y)); }
+        }
+        None
+    }
+
+    // Food positions and poison flags are parallel; only touch them through these helpers
+    pub(crate) fn add_food(&mut self, pos: (f64, f64)) {
+        self.food_chunks.insert_point(pos.0, pos.1, self.food.len());
+        self.food.push(pos);
+        let poison = Math::random() < self.poison_ratio;
+        self.food_poison.push(poison);
+        if !poison { self.ledger.food_grown += FOOD_ENERGY; }
+        self.fertility.add(pos.0, pos.1, -FERTILITY_UPTAKE);
+    }
+
+    // Puts back an existing item (e.g. one that was carried), keeping its poison flag
+    pub(crate) fn place_food(&mut self, pos: (f64, f64), poison: bool) {
+        self.food_chunks.insert_point(pos.0, pos.1, self.food.len());
+        self.food.push(pos);
+        self.food_poison.push(poison);
+    }
+
+    // Closest item no more than `range` away on either axis, searching outward tile by tile
+    pub(crate) fn nearest_food(&self, x: f64, y: f64, range: f64) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        for k in 0..=self.food_chunks.max_ring() {
+            // Everything in ring k is at least (k - 1) tiles away
+            let floor = k.saturating_sub(1) as f64 * self.food_chunks.size();
+            if floor > range || best.is_some_and(|(_, d)| d <= floor) { break; }
+            for idx in self.food_chunks.ring(x, y, k) {
+                let (dx, dy) = (self.food[idx].0 - x, self.food[idx].1 - y);
+                if dx.abs() > range || dy.abs() > range { continue; }
+                let dist = dx.dhypot(dy);
+                if best.is_none_or(|(b, d)| dist < d || (dist == d && idx < b)) { best = Some((idx, dist)); }
+            }
+        }
+        best
+    }
+
+    // Picks up the nearest item within `radius`, taking it out of the world without respawning it
+    pub(crate) fn grab_food(&mut self, x: f64, y: f64, radius: f64) -> Option<bool> {
+        let (idx, dist) = self.nearest_food(x, y, radius)?;
+        if dist >= radius { return None; }
+        let poison = self.food_poison[idx];
+        self.remove_food(idx);
+        Some(poison)
+    }
+
+    pub(crate) fn remove_food(&mut self, idx: usize) {
+        let last = self.food.len() - 1;
+        self.food_chunks.remove_point(self.food[idx].0, self.food[idx].1, idx);
+        if idx != last { self.food_chunks.relabel_point(self.food[last].0, self.food[last].1, last, idx); }
+        self.food.swap_remove(idx);
+        self.food_poison.swap_remove(idx);
+    }
+
+    pub(crate) fn truncate_food(&mut self, count: usize) {
+        for k in count..self.food.len() { self.food_chunks.remove_point(self.food[k].0, self.food[k].1, k); }
+        self.ledger.decay += self.food_poison.iter().skip(count).filter(|p| !**p).count() as f64 * FOOD_ENERGY;
+        self.food.truncate(count);
+        self.food_poison.truncate(count);
+    }
+
+    pub(crate) fn consume_food(&mut self, idx: usize) {
+        self.remove_food(idx);
+        match self.food_respawn_policy {
+            FoodRespawnPolicy::Instant => { let pos = self.new_food_position(); self.add_food(pos); }
+            FoodRespawnPolicy::Delayed => self.pending_food.push(self.tick + self.food_respawn_delay),
+            FoodRespawnPolicy::NearExisting => { let pos = self.position_near_existing_food(); self.add_food(pos); }
+            FoodRespawnPolicy::Finite => {}
+        }
+    }
+
+    // Releases delayed food whose timer ran out
+    pub(crate) fn update_pending_food(&mut self) {
+        let tick = self.tick;
+        let due = self.pending_food.iter().filter(|&&t| t <= tick).count();
+        if due == 0 { return; }
+        self.pending_food.retain(|&t| t > tick);
+        for _ in 0..due { let pos = self.new_food_position(); self.add_food(pos); }
+    }
+
+    fn position_near_existing_food(&self) -> (f64, f64) {
+        if self.food.is_empty() { return self.new_food_position(); }
+        let (cx, cy) = self.food[(Math::random() * self.food.len() as f64) as usize];
+        self.free_position_near(cx, cy, FOOD_CLUSTER_SPREAD).unwrap_or_else(|| self.new_food_position())
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+
+use crate::constants::GENE_FLOW_TICKS;
+use crate::Simulation;
+
+// A birth, drawn as lines from each parent to the newborn that fade out over GENE_FLOW_TICKS
+pub struct GeneFlowEdge {
+    pub tick: u64,
+    pub child: (f64, f64),
+    // Father is None if he died during the pregnancy
+    pub parents: [Option<(f64, f64)>; 2],
+}
+
+#[derive(Default)]
+pub struct GeneFlow {
+    pub enabled: bool,
+    pub edges: VecDeque<GeneFlowEdge>,
+}
+
+impl Simulation {
+    pub(crate) fn note_gene_flow(&mut self, child: (f64, f64), mother: usize, father: (usize, u64)) {
+        if !self.gene_flow.enabled { return; }
+        let (f, father_id) = father;
+        let father_pos = (!self.agents.vacant[f] && self.agents.ids[f] == father_id).then(|| self.agents.positions[f]);
+        let flow = &mut self.gene_flow;
+        while flow.edges.front().is_some_and(|e| self.tick - e.tick >= GENE_FLOW_TICKS) { flow.edges.pop_front(); }
+        flow.edges.push_back(GeneFlowEdge { tick: self.tick, child, parents: [Some(self.agents.positions[mother]), father_pos] });
+    }
+
+    pub(crate) fn render_gene_flow(&self, context: &web_sys::CanvasRenderingContext2d) {
+        context.set_line_width(1.0);
+        context.set_stroke_style_str("#ffffff");
+        for e in &self.gene_flow.edges {
+            let age = self.tick.saturating_sub(e.tick);
+            if age >= GENE_FLOW_TICKS { continue; }
+            context.set_global_alpha(0.6 * (1.0 - age as f64 / GENE_FLOW_TICKS as f64));
+            for (px, py) in e.parents.iter().flatten() {
+                context.begin_path(); context.move_to(*px, *py); context.line_to(e.child.0, e.child.1); context.stroke();
+            }
+        }
+        context.set_global_alpha(1.0);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::fixed::DetMath;
+use crate::rng::Math;
+
+// Does the segment (ax, ay) -> (bx, by) pass within `r` of (cx, cy)?
+pub fn segment_hits_circle(ax: f64, ay: f64, bx: f64, by: f64, cx: f64, cy: f64, r: f64) -> bool {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 { (((cx - ax) * dx + (cy - ay) * dy) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+    (ax + t * dx - cx).dhypot(ay + t * dy - cy) < r
+}
+
+// Terrain feature anywhere in the world; `radius` is (minimum, random extra)
+pub fn random_circle(width: f64, height: f64, radius: (f64, f64)) -> (f64, f64, f64) {
+    (Math::random() * width, Math::random() * height, radius.0 + Math::random() * radius.1)
+}
+
+// Distance from (px, py) to the segment (ax, ay) -> (bx, by)
+pub fn point_segment_distance(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    point_segment_distance_sq(px, py, ax, ay, bx, by).sqrt()
+}
+
+// Squared, for cheap comparisons
+pub fn point_segment_distance_sq(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 { (((px - ax) * dx + (py - ay) * dy) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+    let (ex, ey) = (ax + t * dx - px, ay + t * dy - py);
+    ex * ex + ey * ey
+}
+
+// z of (b - a) x (c - a): positive when c is left of a -> b
+pub fn cross(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+// Shortest distance between two segments; 0 when they cross
+pub fn segment_distance(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> f64 {
+    let (d1, d2, d3, d4) = (cross(c, d, a), cross(c, d, b), cross(a, b, c), cross(a, b, d));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 { return 0.0; }
+    point_segment_distance(a.0, a.1, c.0, c.1, d.0, d.1)
+        .min(point_segment_distance(b.0, b.1, c.0, c.1, d.0, d.1))
+        .min(point_segment_distance(c.0, c.1, a.0, a.1, b.0, b.1))
+        .min(point_segment_distance(d.0, d.1, a.0, a.1, b.0, b.1))
+}
+
+// [x0, y0, x1, y1, ...], the layout typed arrays handed to JS use
+pub fn flatten_points(points: &[(f64, f64)]) -> Vec<f64> { points.iter().flat_map(|&(x, y)| [x, y]).collect() }
+
+// [x0, y0, r0, x1, y1, r1, ...]
+pub fn flatten_circles(circles: &[(f64, f64, f64)]) -> Vec<f64> { circles.iter().flat_map(|&(x, y, r)| [x, y, r]).collect() }
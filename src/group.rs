@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+use crate::constants::TRIBE_COUNT;
+use crate::Simulation;
+
+#[derive(Serialize)]
+pub struct GroupStats {
+    count: usize,
+    tribe_counts: [usize; TRIBE_COUNT],
+    avg_energy: f64,
+    avg_age: f64,
+    avg_offspring: f64,
+    avg_body_size: f64,
+}
+
+impl Simulation {
+    // Living agents inside the rectangle (`w`/`h` may be negative), remembered by id so the
+    // group doesn't pick up whoever is later born into a member's slot
+    pub(crate) fn select_rect(&mut self, x: f64, y: f64, w: f64, h: f64) -> usize {
+        let (x0, x1, y0, y1) = (x.min(x + w), x.max(x + w), y.min(y + h), y.max(y + h));
+        self.group = (0..self.agents.len()).filter(|&i| {
+            let (ax, ay) = self.agents.positions[i];
+            !self.agents.vacant[i] && ax >= x0 && ax <= x1 && ay >= y0 && ay <= y1
+        }).map(|i| (i, self.agents.ids[i])).collect();
+        self.group.len()
+    }
+
+    // Slots of the group members still alive
+    pub(crate) fn group_members(&self) -> Vec<usize> {
+        self.group.iter().filter(|&&(i, id)| !self.agents.vacant[i] && self.agents.ids[i] == id).map(|&(i, _)| i).collect()
+    }
+
+    pub(crate) fn group_stats(&self) -> GroupStats {
+        let members = self.group_members();
+        let n = members.len().max(1) as f64;
+        let mean = |f: &dyn Fn(usize) -> f64| members.iter().map(|&i| f(i)).sum::<f64>() / n;
+        let mut tribe_counts = [0; TRIBE_COUNT];
+        for &i in &members { tribe_counts[self.agents.tribes[i]] += 1; }
+        GroupStats {
+            count: members.len(),
+            tribe_counts,
+            avg_energy: mean(&|i| self.agents.energies[i]),
+            avg_age: mean(&|i| (self.tick - self.agents.birth_ticks[i]) as f64),
+            avg_offspring: mean(&|i| self.agents.offspring_counts[i] as f64),
+            avg_body_size: mean(&|i| self.agents.traits[i].body_size),
+        }
+    }
+
+    // Members die as they would of any other cause (corpse, champion record, slot refill)
+    pub(crate) fn kill_group(&mut self) -> usize {
+        let members = self.group_members();
+        for &i in &members { self.die(i); }
+        self.group.clear();
+        members.len()
+    }
+}
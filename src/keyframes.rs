@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+
+use wasm_bindgen::JsValue;
+
+use crate::constants::{ENERGY_CAP, KEYFRAME_CAPACITY};
+use crate::Simulation;
+
+pub struct KeyframeAgent {
+    x: f32,
+    y: f32,
+    energy: f32,
+    tribe: u8,
+}
+
+// Where everything was at one tick: positions and energies only, no brains
+pub struct Keyframe {
+    pub tick: u64,
+    agents: Vec<KeyframeAgent>,
+    predators: Vec<(f32, f32)>,
+}
+
+impl Keyframe {
+    pub fn agent_count(&self) -> usize { self.agents.len() }
+    pub fn predator_count(&self) -> usize { self.predators.len() }
+}
+
+// Oldest frames are dropped once KEYFRAME_CAPACITY is reached
+#[derive(Default)]
+pub struct Keyframes {
+    pub interval: u64,
+    pub frames: VecDeque<Keyframe>,
+}
+
+impl Simulation {
+    pub(crate) fn record_keyframe(&mut self) {
+        if self.keyframes.interval == 0 || !self.tick.is_multiple_of(self.keyframes.interval) { return; }
+        let agents = (0..self.agents.len()).filter(|&i| !self.agents.vacant[i]).map(|i| KeyframeAgent {
+            x: self.agents.positions[i].0 as f32,
+            y: self.agents.positions[i].1 as f32,
+            energy: self.agents.energies[i] as f32,
+            tribe: self.agents.tribes[i] as u8,
+        }).collect();
+        let predators = self.predators.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+        if self.keyframes.frames.len() == KEYFRAME_CAPACITY { self.keyframes.frames.pop_front(); }
+        self.keyframes.frames.push_back(Keyframe { tick: self.tick, agents, predators });
+    }
+
+    // Recorded agents and predators over today's terrain, through the live camera
+    pub(crate) fn render_keyframe(&self, frame: &Keyframe, context: &web_sys::CanvasRenderingContext2d) -> Result<(), JsValue> {
+        context.set_fill_style_str(&self.theme.background);
+        context.fill_rect(0.0, 0.0, self.screen_w, self.screen_h);
+        context.save();
+        context.scale(self.zoom, self.zoom)?;
+        context.translate(-self.view_x, -self.view_y)?;
+        context.set_stroke_style_str("#222");
+        context.set_line_width(5.0);
+        context.stroke_rect(0.0, 0.0, self.width, self.height);
+
+        let circles = |list: &[(f64, f64, f64)], color: &str| -> Result<(), JsValue> {
+            context.set_fill_style_str(color);
+            for (x, y, r) in list { context.begin_path(); context.arc(*x, *y, *r, 0.0, TAU)?; context.fill(); }
+            Ok(())
+        };
+        circles(&self.mud, &self.theme.mud)?;
+        circles(&self.water, "#1e4f8a")?;
+        circles(&self.shelters, "rgba(0, 255, 170, 0.08)")?;
+        circles(&self.rocks, &self.theme.rock)?;
+
+        context.set_fill_style_str(&self.theme.predator);
+        for (x, y) in &frame.predators { context.begin_path(); context.arc(*x as f64, *y as f64, 8.0, 0.0, TAU)?; context.fill(); }
+        for a in &frame.agents {
+            context.set_fill_style_str(&self.palette[a.tribe as usize]);
+            context.set_global_alpha((a.energy as f64 / ENERGY_CAP).clamp(0.3, 1.0));
+            context.begin_path(); context.arc(a.x as f64, a.y as f64, 5.0, 0.0, TAU)?; context.fill();
+        }
+        context.set_global_alpha(1.0);
+        context.restore();
+        Ok(())
+    }
+}
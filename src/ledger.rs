@@ -0,0 +1,86 @@
+use serde::Serialize;
+
+use crate::constants::*;
+use crate::Simulation;
+
+// Running totals of the energy entering, moving around in and leaving the agents' food chain:
+// agent reserves and stomachs, food lying around or being carried, and corpses. Predators and
+// scavengers sit outside it, so whatever they take counts as leaving.
+#[derive(Serialize, Clone, Default)]
+pub struct EnergyLedger {
+    // What the food chain held when the books were (re)opened
+    pub opening_stock: f64,
+    // Entering
+    pub food_grown: f64,
+    pub sunlight: f64,
+    // Larvae feeding in mud
+    pub grazing: f64,
+    // Starting energy of newborns and newcomers
+    pub births: f64,
+    pub corpses: f64,
+    // Handed out with no matching loss: predator grooming, tribe boosts, cannibal kills
+    pub rewards: f64,
+    // Moving within the food chain; these net to zero and are only here to show the flow
+    pub eaten: f64,
+    pub carrion: f64,
+    pub digested: f64,
+    pub parental: f64,
+    pub stolen: f64,
+    // Leaving
+    pub metabolism: f64,
+    // Fights, poison, retaliation and what's lost in a theft
+    pub damage: f64,
+    pub reproduction: f64,
+    pub predation: f64,
+    // Reserves and stomach contents of the dead
+    pub deaths: f64,
+    // Corpses rotting, and food cleared away (e.g. by lowering the food count)
+    pub decay: f64,
+    pub scavenged: f64,
+    // Net change from clamping energies to [0, ENERGY_CAP] and from fixed-point snapping
+    pub adjustments: f64,
+    // Filled in on request: current holdings, and the part of their change since opening that
+    // no entry explains (should stay at rounding-error size)
+    pub stock: f64,
+    pub unaccounted: f64,
+}
+
+impl EnergyLedger {
+    pub fn inflow(&self) -> f64 { self.food_grown + self.sunlight + self.grazing + self.births + self.corpses + self.rewards }
+
+    pub fn outflow(&self) -> f64 {
+        self.metabolism + self.damage + self.reproduction + self.predation + self.deaths + self.decay + self.scavenged
+    }
+}
+
+impl Simulation {
+    // Adds up to `amount` to agent i, stopping at ENERGY_CAP; the overflow is booked as an
+    // adjustment. Returns what was actually added.
+    pub(crate) fn gain_energy(&mut self, i: usize, amount: f64) -> f64 {
+        let before = self.agents.energies[i];
+        self.agents.energies[i] = (before + amount).min(ENERGY_CAP);
+        let gained = self.agents.energies[i] - before;
+        self.ledger.adjustments += gained - amount;
+        gained
+    }
+
+    pub(crate) fn food_chain_energy(&self) -> f64 {
+        let agents: f64 = (0..self.agents.len()).filter(|&i| !self.agents.vacant[i])
+            .map(|i| self.agents.energies[i] + self.agents.stomachs[i]).sum();
+        let carried = self.agents.carrying.iter().filter(|c| **c == Some(false)).count();
+        let food = self.food_poison.iter().filter(|p| !**p).count() + carried;
+        agents + food as f64 * FOOD_ENERGY + self.corpses.iter().map(|c| c.energy).sum::<f64>()
+    }
+
+    // Starts the books over from what the food chain holds now (e.g. after a reseed)
+    pub(crate) fn reset_ledger(&mut self) {
+        self.ledger = EnergyLedger { opening_stock: self.food_chain_energy(), ..EnergyLedger::default() };
+    }
+
+    pub(crate) fn energy_ledger(&self) -> EnergyLedger {
+        let stock = self.food_chain_energy();
+        let l = &self.ledger;
+        let unaccounted = stock - l.opening_stock - (l.inflow() - l.outflow() + l.adjustments);
+        EnergyLedger { stock, unaccounted, ..l.clone() }
+    }
+}
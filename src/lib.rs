@@ -1,31 +1,192 @@
 use wasm_bindgen::prelude::*;
-use js_sys::Math;
+use wasm_bindgen::{Clamped, JsCast};
+use rng::Math;
+use fixed::DetMath;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::f64::consts::{PI, TAU};
 
 // MODULES
 mod constants;
 mod brain;
 mod spatial_grid;
+mod lineage;
+mod novelty;
+mod fitness;
+mod species;
+mod traits;
+mod geometry;
+mod flow_field;
+mod rng;
+mod agents;
+mod food;
+mod territory;
+mod scavengers;
+mod parasites;
+mod fertility;
+mod predators;
+mod trails;
+mod flocking;
+mod selection;
+mod crossover;
+mod population;
+mod snapshot;
+mod keyframes;
+mod extinction;
+mod curriculum;
+mod events;
+mod tribe_params;
+mod observations;
+mod stats;
+mod memory;
+mod clock;
+mod fixed;
+mod lockstep;
+mod resize;
+mod chunks;
+mod tracking;
+mod group;
+mod gene_flow;
+mod death_map;
+mod cell_grid;
+mod theft;
+mod cannibalism;
+mod symbiosis;
+mod barriers;
+mod scenarios;
+mod benchmark;
+mod pipeline;
+mod ledger;
+mod voice_history;
+mod quality;
+mod query;
+mod theme;
+mod sprites;
 
-use brain::Brain;
+use brain::{Brain, BrainGenome};
 use constants::*;
 use spatial_grid::SpatialGrid;
+use cell_grid::CellGrid;
+use lineage::Lineage;
+use novelty::{Descriptor, NoveltyArchive};
+pub use fitness::FitnessConfig;
+use fitness::AgentStats;
+use species::SpeciesTracker;
+use traits::Traits;
+use geometry::{flatten_circles, flatten_points, random_circle, segment_hits_circle};
+use flow_field::FlowField;
+use agents::{Agents, Birth, Gestation};
+pub use food::{FoodDistribution, FoodRespawnPolicy};
+pub use predators::PredatorTargeting;
+pub use selection::ParentPool;
+pub use crossover::CrossoverStrategy;
+pub use events::EventKind;
+pub use resize::ResizeMode;
+pub use scenarios::Scenario;
+use food::Hotspot;
+use territory::TerritoryMap;
+use scavengers::{Corpse, Scavenger};
+use fertility::FertilityMap;
+use trails::TrailMap;
+use theme::Theme;
+use sprites::{Sprite, Sprites};
+use population::Genome;
+use snapshot::StateSnapshot;
+use keyframes::Keyframes;
+use curriculum::Curriculum;
+use tribe_params::{Intervention, TribeParams};
+use stats::StatsHistory;
+use quality::Quality;
+use lockstep::{Command, InputFrame, Lockstep};
+use chunks::ChunkIndex;
+use tracking::Track;
+use gene_flow::GeneFlow;
+use death_map::{DeathCause, DeathMap};
+use barriers::Barrier;
+use benchmark::Benchmark;
+use pipeline::{Pipeline, Stage};
+use ledger::EnergyLedger;
+use voice_history::VoiceHistory;
+
+#[derive(Serialize)]
+struct CameraState { x: f64, y: f64, zoom: f64 }
+
+// Spectator annotation pinned to a world position
+struct Marker { x: f64, y: f64, label: String, color: String }
 
 #[wasm_bindgen]
 pub struct Simulation {
-    positions: Vec<(f64, f64)>, 
-    angles: Vec<f64>,
-    energies: Vec<f64>,
-    brains: Vec<Brain>,
-    colors: Vec<String>,
-    voices: Vec<f64>,
+    agents: Agents,
     
     food: Vec<(f64, f64)>, 
+    food_poison: Vec<bool>,
+    pending_food: Vec<u64>,
+    hotspots: Vec<Hotspot>,
     predators: Vec<(f64, f64)>,
+    predator_energies: Vec<f64>,
+    predator_angles: Vec<f64>,
+    predator_stamina: Vec<f64>,
+    // Exhausted predators crawl until their stamina is full again
+    predator_resting: Vec<bool>,
+    // Stable id of the agent each predator is locked onto (Random targeting only)
+    predator_targets: Vec<Option<u64>>,
+    // Where each predator was heading for prey last tick (None = wandering), for drawing
+    predator_hunts: Vec<Option<(f64, f64)>>,
+    // Centre of each predator's territory, used when predator_territory > 0
+    predator_homes: Vec<(f64, f64)>,
+    // Territory radius; 0 = predators roam the whole world
+    predator_territory: f64,
+    corpses: Vec<Corpse>,
+    scavengers: Vec<Scavenger>,
+    // Free-floating parasites; attached ones are counted per agent
+    parasites: Vec<(f64, f64)>,
     
     rocks: Vec<(f64, f64, f64)>, 
     mud: Vec<(f64, f64, f64)>,  
+    // Safe zones: predators can't enter or target inside, and no food spawns there
+    shelters: Vec<(f64, f64, f64)>,
+    // Lakes: drinking spots, and wading in washes parasites off
+    water: Vec<(f64, f64, f64)>,
+    // Walls and convex polygons; they block like rocks
+    barriers: Vec<Barrier>,
+    // Set by load_scenario: circles food grows in and newcomers start in (one per tribe, cycling);
+    // empty = anywhere
+    food_zones: Vec<(f64, f64, f64)>,
+    spawn_zones: Vec<(f64, f64, f64)>,
 
     grid: SpatialGrid,
+    rock_chunks: ChunkIndex,
+    barrier_chunks: ChunkIndex,
+    food_chunks: ChunkIndex,
+    nav: FlowField,
+    territory: TerritoryMap,
+    fertility: FertilityMap,
+    trails: TrailMap,
+    lineage: Lineage,
+    novelty_archive: NoveltyArchive,
+    species: SpeciesTracker,
+    tick: u64,
+    births: u64,
+    juvenile_deaths: u64,
+    // Agents and predators killed in combat, over the whole run
+    kills: u64,
+    // Wholesome food items eaten by agents, over the whole run
+    meals: u64,
+    benchmark: Option<Benchmark>,
+    pipeline: Pipeline,
+    ledger: EnergyLedger,
+    // Successful steals, and how many of them the victim punished
+    thefts: u64,
+    retaliations: u64,
+    // Predators groomed by cleaner agents
+    groomings: u64,
+    stats_history: StatsHistory,
+    voice_history: VoiceHistory,
+    quality: Quality,
+    lockstep: Lockstep,
+    // Events since the last get_audio_frame()
+    audio_kills: u32,
+    audio_births: u32,
 
     // MERGED: Added Log Buffer from second block
     log_buffer: Vec<String>,
@@ -35,54 +196,257 @@ pub struct Simulation {
     
     mutation_rate: f64,
     predator_speed: f64,       
+    stamina_max: f64,
+    stamina_drain: f64,
+    stamina_recovery: f64,
+    rest_speed_factor: f64,
+    predator_targeting: PredatorTargeting,
+    predator_fov: f64,
+    predator_vision_range: f64,
     reproduction_threshold: f64, 
+    parent_cost: f64,
+    offspring_energy: f64,
+    reproduction_cooldown: u64,
+    tournament_size: usize,
+    parent_pool: ParentPool,
+    crossover: CrossoverStrategy,
+    // Chance per live connection of being cut in each offspring
+    sparsity_bias: f64,
+    eat_radius: f64,
+    kill_radius: f64,
+    size_scaled_radii: bool,
+    predator_pathfinding: bool,
+    food_respawn_policy: FoodRespawnPolicy,
+    food_respawn_delay: u64,
+    food_distribution: FoodDistribution,
+    poison_ratio: f64,
+    territory_stress: f64,
+    wind: (f64, f64),
+    wind_turbulence: f64,
+    gestation_ticks: u64,
+    // Deaths free their slot instead of triggering a rebirth; well-fed agents breed on their own
+    dynamic_population: bool,
+    carrying_capacity: usize,
+    starvation_grace: u32,
+    density_cost: f64,
+    density_radius: f64,
+    show_fertility: bool,
+    gene_flow: GeneFlow,
+    deaths: DeathMap,
+    show_deaths: bool,
+    show_predator_state: bool,
+    // Built-in boids forces: (separation, alignment, cohesion)
+    flocking: (f64, f64, f64),
+    sensor_noise: f64,
+    seed: u64,
+    fixed_point: bool,
+    // Free-form JSON describing the run (who, which hypothesis, which config), carried into exports
+    run_metadata: String,
+    keyframes: Keyframes,
+    tribe_alive: [bool; TRIBE_COUNT],
+    // Deaths in a row whose slot went to a random newcomer because no parents could breed
+    newcomer_streak: usize,
+    population_extinct: bool,
+    // Best genomes seen at death, best first, for auto-restart
+    champions: Vec<(f64, Genome)>,
+    auto_restart: bool,
+    extinction_fn: Option<js_sys::Function>,
+    curriculum: Option<Curriculum>,
+    // Where the latest event of each EventKind happened
+    last_events: [Option<(f64, f64)>; 3],
+    tribe_params: [TribeParams; TRIBE_COUNT],
+    // Per-tick energy gift and speed multiplier, each with an expiry tick
+    boosts: [Option<Intervention>; TRIBE_COUNT],
+    handicaps: [Option<Intervention>; TRIBE_COUNT],
+    // (inputs, outputs) records collected while record_observations runs
+    observations: Option<Vec<u8>>,
+    track: Option<Track>,
+    // Remaining ticks to play in slow motion, and calls to step() swallowed so far
+    slow_motion: u32,
+    slow_motion_frame: u32,
+    // Wall time handed to step_dt that hasn't made up a whole tick yet, in ms
+    step_accumulator: f64,
+    compass_sensors: bool,
+    // Newborns start as larvae that feed in mud (see update_stage)
+    metamorphosis: bool,
+    // Activity tires agents out, and tired brains get sloppy until they rest
+    fatigue: bool,
+    // Agents also eat corpses and prey on much weaker rivals
+    cannibalism: bool,
+    novelty_search: bool,
+    fitness: FitnessConfig,
+    fitness_fn: Option<js_sys::Function>,
+    speciation: bool,
+    // Player-controlled and highlighted agents as (slot, id), so neither passes to a newborn
+    possessed: Option<(usize, u64)>,
+    selected: Option<(usize, u64)>,
+    // Box-selected agents as (slot, id)
+    group: Vec<(usize, u64)>,
+    markers: Vec<Marker>,
+    palette: Vec<String>,
+    theme: Theme,
+    sprites: Sprites,
+    manual_controls: [f64; BRAIN_OUTPUTS],
+    // By agent id, so a controller goes with its agent and not the slot
+    external_controllers: HashMap<u64, js_sys::Function>,
     view_x: f64, view_y: f64, zoom: f64,
+    screen_w: f64, screen_h: f64,
 }
 
 #[wasm_bindgen]
 impl Simulation {
     pub fn new(width: f64, height: f64) -> Simulation {
-        let mut positions = Vec::new();
-        let mut angles = Vec::new();
-        let mut energies = Vec::new();
-        let mut brains = Vec::new();
-        let mut colors = Vec::new();
-        let mut voices = Vec::new();
-        let mut food = Vec::new();
-        let mut predators = Vec::new();
-        let mut rocks = Vec::new();
-        let mut mud = Vec::new();
-
-        let color_palette = ["#ff00cc", "#ccff00", "#00ccff", "#ffcc00"];
-
-        for _ in 0..AGENT_COUNT {
-            positions.push((Math::random() * width, Math::random() * height));
-            angles.push(Math::random() * 6.28);
-            energies.push(STARTING_ENERGY);
-            brains.push(Brain::new());
-            let color_idx = (Math::random() * 4.0) as usize;
-            colors.push(color_palette[color_idx].to_string());
-            voices.push(0.0);
-        }
-
-        for _ in 0..FOOD_COUNT { food.push((Math::random() * width, Math::random() * height)); }
-        for _ in 0..PREDATOR_COUNT { predators.push((Math::random() * width, Math::random() * height)); }
-        for _ in 0..15 { rocks.push((Math::random() * width, Math::random() * height, 20.0 + Math::random() * 30.0)); }
-        for _ in 0..10 { mud.push((Math::random() * width, Math::random() * height, 40.0 + Math::random() * 60.0)); }
+        let rocks: Vec<_> = (0..ROCK_COUNT).map(|_| random_circle(width, height, ROCK_RADIUS)).collect();
+        let mud = (0..MUD_COUNT).map(|_| random_circle(width, height, MUD_RADIUS)).collect();
+        let water = (0..LAKE_COUNT).map(|_| random_circle(width, height, LAKE_RADIUS)).collect();
+        let shelters: Vec<_> = (0..SHELTER_COUNT).map(|_| random_circle(width, height, SHELTER_RADIUS)).collect();
 
         let grid = SpatialGrid::new(width, height, 100.0);
+        let nav = FlowField::new(width, height, NAV_CELL_SIZE, &[rocks.as_slice(), shelters.as_slice()].concat());
+        let territory = TerritoryMap::new(width, height, TERRITORY_CELL_SIZE);
+        let fertility = FertilityMap::new(width, height, FERTILITY_CELL_SIZE);
+        let trails = TrailMap::new(width, height, TRAIL_CELL_SIZE);
 
-        Simulation { 
-            positions, angles, energies, brains, colors, voices, 
-            food, predators, rocks, mud, grid,
+        let mut sim = Simulation { 
+            agents: Agents::new(),
+            predator_energies: vec![PREDATOR_STARTING_ENERGY; PREDATOR_COUNT],
+            predator_stamina: vec![PREDATOR_STAMINA; PREDATOR_COUNT],
+            predator_resting: vec![false; PREDATOR_COUNT],
+            predator_targets: vec![None; PREDATOR_COUNT],
+            predator_hunts: vec![None; PREDATOR_COUNT],
+            predator_homes: Vec::new(),
+            predator_territory: 0.0,
+            predator_angles: (0..PREDATOR_COUNT).map(|_| Math::random() * TAU).collect(),
+            food: Vec::new(), food_poison: Vec::new(), pending_food: Vec::new(), hotspots: Vec::new(), predators: Vec::new(),
+            corpses: Vec::new(), scavengers: Vec::new(), parasites: Vec::new(), rocks, mud, shelters, water, grid, nav, territory, fertility, trails,
+            barriers: Vec::new(), food_zones: Vec::new(), spawn_zones: Vec::new(),
+            rock_chunks: ChunkIndex::new(width, height, CHUNK_SIZE), food_chunks: ChunkIndex::new(width, height, CHUNK_SIZE),
+            barrier_chunks: ChunkIndex::new(width, height, CHUNK_SIZE),
+            lineage: Lineage::new(),
+            novelty_archive: NoveltyArchive::new(NOVELTY_ARCHIVE_SIZE, NOVELTY_K),
+            species: SpeciesTracker::new(SPECIES_THRESHOLD),
+            tick: 0,
+            births: 0,
+            juvenile_deaths: 0,
+            kills: 0,
+            meals: 0,
+            benchmark: None,
+            pipeline: Pipeline::default(),
+            ledger: EnergyLedger::default(),
+            thefts: 0,
+            retaliations: 0,
+            groomings: 0,
+            stats_history: StatsHistory::default(),
+            voice_history: VoiceHistory::default(),
+            quality: Quality::default(),
+            lockstep: Lockstep::new(0, 0),
+            audio_kills: 0,
+            audio_births: 0,
             // MERGED: Initialize empty log buffer
             log_buffer: Vec::new(),
             width, height, 
             mutation_rate: BASE_MUTATION_RATE,
             predator_speed: 2.2, 
+            stamina_max: PREDATOR_STAMINA,
+            stamina_drain: STAMINA_DRAIN,
+            stamina_recovery: STAMINA_RECOVERY,
+            rest_speed_factor: REST_SPEED_FACTOR,
+            predator_targeting: PredatorTargeting::Nearest,
+            predator_fov: PREDATOR_FOV,
+            predator_vision_range: PREDATOR_VISION_RANGE,
             reproduction_threshold: 60.0, 
+            parent_cost: PARENT_COST,
+            offspring_energy: OFFSPRING_ENERGY,
+            reproduction_cooldown: REPRODUCTION_COOLDOWN,
+            tournament_size: TOURNAMENT_SIZE,
+            parent_pool: ParentPool::Global,
+            crossover: CrossoverStrategy::Uniform,
+            sparsity_bias: 0.0,
+            eat_radius: EAT_RADIUS,
+            kill_radius: PREDATOR_KILL_RADIUS,
+            size_scaled_radii: false,
+            predator_pathfinding: true,
+            food_respawn_policy: FoodRespawnPolicy::Instant,
+            food_respawn_delay: FOOD_RESPAWN_DELAY,
+            food_distribution: FoodDistribution::Uniform,
+            poison_ratio: 0.0,
+            territory_stress: TERRITORY_STRESS,
+            wind: (0.0, 0.0),
+            wind_turbulence: 0.0,
+            gestation_ticks: GESTATION_TICKS,
+            dynamic_population: false,
+            carrying_capacity: CARRYING_CAPACITY,
+            starvation_grace: STARVATION_GRACE,
+            density_cost: DENSITY_COST,
+            density_radius: DENSITY_RADIUS,
+            show_fertility: false,
+            gene_flow: GeneFlow::default(),
+            deaths: DeathMap::new(width, height, DEATH_MAP_CELL_SIZE),
+            show_deaths: false,
+            show_predator_state: false,
+            flocking: (0.0, 0.0, 0.0),
+            sensor_noise: 0.0,
+            seed: (Math::random() * u32::MAX as f64) as u64,
+            fixed_point: false,
+            run_metadata: "{}".to_string(),
+            keyframes: Keyframes { interval: KEYFRAME_INTERVAL, ..Keyframes::default() },
+            tribe_alive: [true; TRIBE_COUNT],
+            newcomer_streak: 0,
+            population_extinct: false,
+            champions: Vec::new(),
+            auto_restart: false,
+            extinction_fn: None,
+            curriculum: None,
+            last_events: [None; 3],
+            tribe_params: [TribeParams::default(); TRIBE_COUNT],
+            boosts: [None; TRIBE_COUNT],
+            handicaps: [None; TRIBE_COUNT],
+            observations: None,
+            track: None,
+            slow_motion: 0,
+            slow_motion_frame: 0,
+            step_accumulator: 0.0,
+            compass_sensors: true,
+            metamorphosis: false,
+            fatigue: false,
+            cannibalism: false,
+            novelty_search: false,
+            fitness: FitnessConfig::energy_only(),
+            fitness_fn: None,
+            speciation: false,
+            possessed: None,
+            selected: None,
+            group: Vec::new(),
+            markers: Vec::new(),
+            palette: TRIBE_COLORS.iter().map(|c| c.to_string()).collect(),
+            theme: Theme::default(),
+            sprites: Sprites::default(),
+            manual_controls: [0.0; BRAIN_OUTPUTS],
+            external_controllers: HashMap::new(),
             view_x: 0.0, view_y: 0.0, zoom: 1.0,
-        }
+            screen_w: width, screen_h: height,
+        };
+        // Terrain first, so every spawn below can avoid the rocks
+        for _ in 0..FOOD_COUNT { let p = sim.new_food_position(); sim.add_food(p); }
+        for _ in 0..PREDATOR_COUNT { let p = sim.random_open_position(); sim.predators.push(p); sim.predator_homes.push(p); }
+        for _ in 0..PARASITE_COUNT { let p = sim.random_free_position(); sim.parasites.push(p); }
+        sim.reindex_rocks();
+        for _ in 0..SCAVENGER_COUNT { let s = sim.spawn_scavenger(); sim.scavengers.push(s); }
+        for _ in 0..AGENT_COUNT { sim.spawn_newcomer(None); }
+        sim.audio_births = 0; // the initial population isn't an event
+        sim
+    }
+
+    // Like new, but terrain, population and every later shared draw come from `seed`, with
+    // fixed-point mode switched on first if asked: two simulations built with the same arguments
+    // replay identically, tick for tick
+    pub fn with_seed(width: f64, height: f64, seed: u64, fixed_point: bool) -> Simulation {
+        fixed::set_enabled(fixed_point);
+        rng::reseed(Some(seed));
+        let mut sim = Simulation::new(width, height);
+        sim.seed = seed;
+        sim.fixed_point = fixed_point;
+        sim
     }
 
     // --- MERGED: LOGGING FUNCTION ---
@@ -99,8 +463,9 @@ impl Simulation {
     pub fn get_agent_at(&self, x: f64, y: f64) -> i32 {
         let mut best_dist = 30.0; 
         let mut best_idx = -1;
-        for i in 0..self.positions.len() {
-            let dist = (self.positions[i].0 - x).hypot(self.positions[i].1 - y);
+        for i in 0..self.agents.positions.len() {
+            if self.agents.vacant[i] { continue; }
+            let dist = (self.agents.positions[i].0 - x).hypot(self.agents.positions[i].1 - y);
             if dist < best_dist {
                 best_dist = dist;
                 best_idx = i as i32;
@@ -109,120 +474,753 @@ impl Simulation {
         best_idx
     }
 
+    // Nearest entity of any kind under a screen position (as an object with a `kind` tag), or null
+    pub fn query_at(&self, screen_x: f64, screen_y: f64) -> JsValue {
+        let x = screen_x / self.zoom + self.view_x;
+        let y = screen_y / self.zoom + self.view_y;
+        match self.entity_at(x, y, HOVER_RADIUS / self.zoom) {
+            Some(info) => serde_wasm_bindgen::to_value(&info).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    pub fn get_agent_ids(&self) -> Box<[u64]> { self.agents.ids.clone().into_boxed_slice() }
+    // Current slot of a stable id, or -1 once that agent has died
+    pub fn get_agent_index(&self, id: u64) -> i32 {
+        self.agents.ids.iter().zip(&self.agents.vacant).position(|(&x, &v)| x == id && !v).map_or(-1, |i| i as i32)
+    }
+
     pub fn get_agent_brain(&self, index: usize) -> JsValue {
-        if index < self.brains.len() {
-            serde_wasm_bindgen::to_value(&self.brains[index]).unwrap()
+        if index < self.agents.brains.len() {
+            serde_wasm_bindgen::to_value(&self.agents.brains[index].export()).unwrap()
+        } else {
+            JsValue::NULL
+        }
+    }
+
+    // Accepts any brain get_agent_brain has ever produced, migrated to the current topology
+    pub fn set_agent_brain(&mut self, index: usize, brain: JsValue) -> Result<(), JsValue> {
+        if index >= self.agents.len() || self.agents.vacant[index] { return Err("no agent at that index".into()); }
+        let genome: BrainGenome = serde_wasm_bindgen::from_value(brain)?;
+        let brain = Brain::from_genome(genome).map_err(|e| JsValue::from_str(&e))?;
+        if self.speciation {
+            self.species.remove(self.agents.species_ids[index]);
+            self.agents.species_ids[index] = self.species.assign(&brain);
+        }
+        self.agents.brains[index] = brain;
+        Ok(())
+    }
+
+    pub fn get_agent_traits(&self, index: usize) -> JsValue {
+        if index < self.agents.traits.len() {
+            serde_wasm_bindgen::to_value(&self.agents.traits[index]).unwrap()
         } else {
             JsValue::NULL
         }
     }
 
+    // Highlights an agent and its sensors in draw()
+    pub fn select_agent(&mut self, index: usize) {
+        self.selected = (index < self.agents.len() && !self.agents.vacant[index]).then(|| (index, self.agents.ids[index]));
+    }
+    pub fn clear_selection(&mut self) { self.selected = None; }
+
+    // --- GROUP SELECTION ---
+    // Selects every living agent in a world-space rectangle; returns how many
+    pub fn select_in_rect(&mut self, x: f64, y: f64, w: f64, h: f64) -> usize { self.select_rect(x, y, w, h) }
+    pub fn clear_group_selection(&mut self) { self.group.clear(); }
+    // Count, tribe mix and mean energy/age/offspring/body size of the members still alive
+    pub fn get_selection_stats(&self) -> JsValue { serde_wasm_bindgen::to_value(&self.group_stats()).unwrap() }
+    // Returns how many died
+    pub fn kill_selection(&mut self) -> usize { self.kill_group() }
+    // Same format as export_population
+    pub fn export_selection_genomes(&self) -> Box<[u8]> {
+        let genomes: Vec<Genome> = self.group_members().into_iter().map(|i| self.genome(i)).collect();
+        population::encode(&genomes, &self.run_metadata, false).into_boxed_slice()
+    }
+
+    // --- MARKERS ---
+    // Labelled pin drawn above everything else, e.g. "this tribe's nest"; `color` is any CSS color
+    pub fn add_marker(&mut self, x: f64, y: f64, label: String, color: String) { self.markers.push(Marker { x, y, label, color }); }
+    pub fn clear_markers(&mut self) { self.markers.clear(); }
+
+    // --- PLAYER CONTROL ---
+    pub fn possess_agent(&mut self, index: usize) {
+        if index < self.agents.len() && !self.agents.vacant[index] { self.possessed = Some((index, self.agents.ids[index])); }
+    }
+    pub fn release_agent(&mut self) { self.possessed = None; }
+    pub fn get_possessed_agent(&self) -> i32 { self.possessed.filter(|&p| self.holds(p)).map_or(-1, |(i, _)| i as i32) }
+    // One value per brain output, in order: turn, thrust, voice, groom, torpor, grab, burrow, steal.
+    // Missing trailing values read as 0.
+    pub fn set_manual_controls(&mut self, controls: &[f64]) {
+        self.manual_controls = std::array::from_fn(|k| controls.get(k).copied().unwrap_or(0.0).clamp(-1.0, 1.0));
+    }
+
+    // --- EXTERNAL CONTROLLERS ---
+    // `callback(inputs: Float64Array) -> [turn, thrust, voice, groom, torpor, grab, burrow, steal]`,
+    // called every tick for agent `index` until it dies. Missing or non-finite values read as 0.
+    pub fn set_external_controller(&mut self, index: usize, callback: js_sys::Function) {
+        if index < self.agents.len() && !self.agents.vacant[index] { self.external_controllers.insert(self.agents.ids[index], callback); }
+    }
+    pub fn clear_external_controller(&mut self, index: usize) {
+        if index < self.agents.len() { self.external_controllers.remove(&self.agents.ids[index]); }
+    }
+    pub fn clear_external_controllers(&mut self) { self.external_controllers.clear(); }
+
+    // --- LOCK-STEP ---
+    // Joins a session of `peer_count` browsers (this one is `peer_id`). From then on interventions
+    // go through queue_command, are sent to the others via get_input_frame each tick, and take
+    // effect LOCKSTEP_INPUT_DELAY ticks later on every peer; only step() once is_frame_ready().
+    // Every peer should build its world with with_seed (same seed, fixed point on) so the worlds
+    // themselves start, and stay, identical.
+    pub fn start_lockstep(&mut self, peer_id: u32, peer_count: u32) {
+        self.lockstep = Lockstep::new(peer_id, peer_count.max(1));
+    }
+    pub fn stop_lockstep(&mut self) { self.lockstep = Lockstep::new(0, 0); }
+    // `{ op: "param", name, value }`, `{ op: "add_shelter", x, y, radius }`, ... (see lockstep.rs);
+    // applied immediately outside a session
+    pub fn queue_command(&mut self, command: JsValue) -> Result<(), JsValue> {
+        let command: Command = serde_wasm_bindgen::from_value(command)?;
+        self.enqueue_command(command).map_err(|e| JsValue::from_str(&e))
+    }
+    // This tick's local commands, to send to every other peer; call once per tick
+    pub fn get_input_frame(&mut self) -> JsValue { serde_wasm_bindgen::to_value(&self.take_input_frame()).unwrap() }
+    pub fn apply_remote_frame(&mut self, frame: JsValue) -> Result<(), JsValue> {
+        let frame: InputFrame = serde_wasm_bindgen::from_value(frame)?;
+        self.receive_frame(frame).map_err(|e| JsValue::from_str(&e))
+    }
+    pub fn is_frame_ready(&self) -> bool { self.frame_ready() }
+
+    // --- LINEAGE ---
+    pub fn export_phylogeny_newick(&self, max_depth: u32) -> String {
+        self.lineage.to_newick(&self.agents.living_ids(), max_depth)
+    }
+
+    // --- RUN METADATA ---
+    // Any JSON-serializable value; stored as text so exports stay self-describing
+    pub fn set_run_metadata(&mut self, metadata: JsValue) -> Result<(), JsValue> {
+        self.run_metadata = js_sys::JSON::stringify(&metadata)?.as_string().unwrap_or_else(|| "null".to_string());
+        Ok(())
+    }
+    pub fn get_run_metadata(&self) -> Result<JsValue, JsValue> { js_sys::JSON::parse(&self.run_metadata) }
+
+    // --- SNAPSHOTS ---
+    // Population, genome, energy and terrain summary of this moment, for diff_states
+    pub fn snapshot_state(&self) -> JsValue { serde_wasm_bindgen::to_value(&self.snapshot()).unwrap() }
+    // What changed from snapshot `a` to snapshot `b` (both from snapshot_state), as b minus a
+    pub fn diff_states(a: JsValue, b: JsValue) -> Result<JsValue, JsValue> {
+        let a: StateSnapshot = serde_wasm_bindgen::from_value(a)?;
+        let b: StateSnapshot = serde_wasm_bindgen::from_value(b)?;
+        Ok(serde_wasm_bindgen::to_value(&snapshot::diff(&a, &b))?)
+    }
+
+    // --- KEYFRAMES ---
+    // Record positions and energies every `ticks` ticks (0 stops recording); clears the buffer
+    pub fn set_keyframe_interval(&mut self, ticks: u64) { self.keyframes = Keyframes { interval: ticks, ..Keyframes::default() }; }
+    pub fn get_keyframe_count(&self) -> usize { self.keyframes.frames.len() }
+    // Tick keyframe `k` was taken at (0 is the oldest kept), or -1 if out of range
+    pub fn get_keyframe_tick(&self, k: usize) -> i64 { self.keyframes.frames.get(k).map_or(-1, |f| f.tick as i64) }
+    // Draws keyframe `k` in place of the live world, for a timeline scrubber
+    pub fn draw_keyframe(&self, k: usize, context: &web_sys::CanvasRenderingContext2d) -> Result<(), JsValue> {
+        let frame = self.keyframes.frames.get(k).ok_or("no keyframe at that index")?;
+        self.render_keyframe(frame, context)
+    }
+
+    // --- OBSERVATIONS ---
+    // Runs `n_ticks` ticks and returns every living agent's (inputs, outputs) pair from each,
+    // for training imitation or RL models offline (see observations.rs for the layout)
+    pub fn record_observations(&mut self, n_ticks: u64) -> Box<[u8]> {
+        self.observations = Some(Vec::new());
+        let end = self.tick + n_ticks;
+        while self.tick < end { self.step(); }
+        observations::finish(self.observations.take().unwrap_or_default()).into_boxed_slice()
+    }
+
+    // --- TRACKING ---
+    // Starts logging agent `index` every tick until it dies, replacing any previous track
+    pub fn track_agent(&mut self, index: usize) {
+        if index < self.agents.len() && !self.agents.vacant[index] { self.track = Some(Track::new(self.agents.ids[index], index)); }
+    }
+    // CSV of tick, position, energy and the six outputs acted on; empty if nothing was tracked
+    pub fn export_track(&self) -> String { self.track.as_ref().map_or(String::new(), |t| t.to_csv()) }
+
+    // --- GENOMES ---
+    // Brains, traits and tribes of the living agents only, decoupled from the world they evolved in.
+    // The run metadata is embedded so the file says where it came from.
+    pub fn export_population(&self) -> Box<[u8]> {
+        population::encode(&self.living_genomes(), &self.run_metadata, false).into_boxed_slice()
+    }
+    // Same, with brain weights quantized to 8 bits per layer: about 8x smaller, for sharing over
+    // URLs or localStorage. import_population reads either format.
+    pub fn export_population_quantized(&self) -> Box<[u8]> {
+        population::encode(&self.living_genomes(), &self.run_metadata, true).into_boxed_slice()
+    }
+    // Best genomes seen at death so far (see set_auto_restart), in the same format
+    pub fn export_champions(&self) -> Box<[u8]> {
+        let genomes: Vec<Genome> = self.champions.iter().map(|(_, g)| g.clone()).collect();
+        population::encode(&genomes, &self.run_metadata, false).into_boxed_slice()
+    }
+
+    // Replaces every agent with the imported genomes at fresh random positions. A fixed-size
+    // population cycles through them to fill all slots; a dynamic one gets one agent per genome.
+    pub fn import_population(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let (genomes, metadata) = population::decode(bytes).map_err(|e| JsValue::from_str(&e))?;
+        if genomes.is_empty() { return Err("population export is empty".into()); }
+        self.reseed(&genomes, genomes.len(), 0.0);
+        if !metadata.is_empty() { self.log_buffer.push(format!("📦 Imported {} genomes from run {}", genomes.len(), metadata)); }
+        Ok(())
+    }
+
+    // Living agents per tribe, by tribe index
     pub fn get_tribe_stats(&self) -> Box<[i32]> {
-        let mut stats = vec![0, 0, 0, 0];
-        for color in &self.colors {
-            match color.as_str() {
-                "#ff00cc" => stats[0] += 1, 
-                "#ccff00" => stats[1] += 1, 
-                "#00ccff" => stats[2] += 1, 
-                "#ffcc00" => stats[3] += 1, 
-                _ => {},
-            }
+        let mut stats = vec![0; TRIBE_COUNT];
+        for (tribe, _) in self.agents.tribes.iter().zip(&self.agents.vacant).filter(|(_, v)| !**v) {
+            stats[*tribe] += 1;
         }
         stats.into_boxed_slice()
     }
+
+    // --- THEMING ---
+    // <img> or ImageBitmap per entity kind; null keeps the vector shape for that kind
+    pub fn set_sprites(&mut self, agent: JsValue, predator: JsValue, food: JsValue) -> Result<(), JsValue> {
+        self.sprites = Sprites { agent: Sprite::from_js(agent)?, predator: Sprite::from_js(predator)?, food: Sprite::from_js(food)? };
+        Ok(())
+    }
+    pub fn clear_sprites(&mut self) { self.sprites = Sprites::default(); }
+    // Tribe colors by index; non-string entries and anything past TRIBE_COUNT are ignored
+    pub fn set_palette(&mut self, colors: Vec<JsValue>) {
+        for (slot, color) in self.palette.iter_mut().zip(colors) {
+            if let Some(c) = color.as_string() { *slot = c; }
+        }
+    }
+    pub fn get_palette(&self) -> Vec<String> { self.palette.clone() }
+    pub fn set_theme(&mut self, background: String, food_color: String, predator_color: String, rock_color: String, mud_color: String) {
+        self.theme = Theme { background, food: food_color, predator: predator_color, rock: rock_color, mud: mud_color };
+    }
     
     pub fn set_mutation_rate(&mut self, rate: f64) { self.mutation_rate = rate; }
+    // Override `mutation_rate`, `speed_modifier` (speed multiplier) or `vision_range` for one
+    // tribe only, e.g. to run a control and a treatment group side by side
+    pub fn set_tribe_param(&mut self, tribe: usize, name: &str, value: f64) -> Result<(), JsValue> {
+        let params = self.tribe_params.get_mut(tribe).ok_or("no such tribe")?;
+        params.set(name, value).map_err(|e| JsValue::from_str(&e))
+    }
+    pub fn clear_tribe_params(&mut self) { self.tribe_params = [TribeParams::default(); TRIBE_COUNT]; }
+    // Interventions: for the next `ticks` ticks every member of `tribe` gains `energy_bonus`
+    // energy per tick, or moves at `speed_multiplier` times its speed. A new call replaces the old one.
+    pub fn boost_tribe(&mut self, tribe: usize, energy_bonus: f64, ticks: u64) {
+        if tribe < TRIBE_COUNT { self.boosts[tribe] = Some(Intervention { value: energy_bonus, until: self.tick + ticks }); }
+    }
+    pub fn handicap_tribe(&mut self, tribe: usize, speed_multiplier: f64, ticks: u64) {
+        if tribe < TRIBE_COUNT { self.handicaps[tribe] = Some(Intervention { value: speed_multiplier.max(0.0), until: self.tick + ticks }); }
+    }
+    pub fn clear_interventions(&mut self) { self.boosts = [None; TRIBE_COUNT]; self.handicaps = [None; TRIBE_COUNT]; }
     pub fn set_predator_speed(&mut self, speed: f64) { self.predator_speed = speed; }
+    pub fn set_predator_count(&mut self, count: usize) { self.resize_predators(count); }
+    // Curriculum mode: predators start few and slow, and every time the mean selection score
+    // beats `fitness_threshold` they gain `speed_step` speed and `count_step` members, up to
+    // `max_level` times. Turning it off restores the speed and count from before.
+    pub fn set_curriculum(&mut self, enabled: bool, fitness_threshold: f64, speed_step: f64, count_step: usize, max_level: u32) {
+        let base = self.curriculum.take().map_or((self.predator_speed, self.predators.len()), |c| c.base);
+        if enabled {
+            self.curriculum = Some(Curriculum { threshold: fitness_threshold, speed_step: speed_step.max(0.0), count_step, max_level, level: 0, base });
+            self.apply_difficulty();
+        } else {
+            self.predator_speed = base.0;
+            self.resize_predators(base.1);
+        }
+    }
+    // Current curriculum level (0 when the curriculum is off)
+    pub fn get_difficulty(&self) -> u32 { self.curriculum.as_ref().map_or(0, |c| c.level) }
+    pub fn set_predator_targeting(&mut self, targeting: PredatorTargeting) { self.predator_targeting = targeting; }
+    // Full cone angle in radians (TAU or more = all-round vision) and how far predators can see
+    pub fn set_predator_vision(&mut self, fov: f64, range: f64) { self.predator_fov = fov.max(0.0); self.predator_vision_range = range.max(0.0); }
+    // Confines each predator to a circle of `radius` around where it stands now, which it patrols
+    // and hunts inside; 0 lets them roam the whole world again
+    pub fn set_predator_territories(&mut self, radius: f64) {
+        self.predator_territory = radius.max(0.0);
+        self.predator_homes = self.predators.clone();
+    }
+    // Sprinting drains `drain` per tick; once empty a predator moves at `rest_speed_factor`
+    // of its speed and regains `recovery` per tick until full. A drain of 0 disables resting.
+    pub fn set_predator_stamina(&mut self, max: f64, drain: f64, recovery: f64, rest_speed_factor: f64) {
+        self.stamina_max = max.max(1.0);
+        self.stamina_drain = drain.max(0.0);
+        self.stamina_recovery = recovery.max(0.0);
+        self.rest_speed_factor = rest_speed_factor.clamp(0.0, 1.0);
+        for s in &mut self.predator_stamina { *s = s.min(self.stamina_max); }
+    }
     pub fn set_reproduction_threshold(&mut self, val: f64) { self.reproduction_threshold = val; }
+    // Energy each parent pays at conception, and what the newborn starts with
+    pub fn set_reproduction_cost(&mut self, parent_cost: f64, offspring_energy: f64) {
+        self.parent_cost = parent_cost.max(0.0);
+        self.offspring_energy = offspring_energy.max(1.0);
+    }
+    // Ticks an agent must wait after parenting before it can be picked again
+    pub fn set_reproduction_cooldown(&mut self, ticks: u64) { self.reproduction_cooldown = ticks; }
+    // Random samples per parent tournament: 1 is random mating, larger is harsher selection
+    pub fn set_tournament_size(&mut self, k: usize) { self.tournament_size = k.clamp(1, MAX_TOURNAMENT_SIZE); }
+    pub fn set_parent_pool(&mut self, pool: ParentPool) { self.parent_pool = pool; }
+    pub fn set_crossover(&mut self, strategy: CrossoverStrategy) { self.crossover = strategy; }
+    // Sparsity-biased mutation: chance that each nonzero connection of an offspring is cut
+    pub fn set_sparsity_bias(&mut self, chance: f64) { self.sparsity_bias = chance.clamp(0.0, 1.0); }
+    // Zeroes every living agent's connection weights below `threshold` in magnitude
+    pub fn prune_brains(&mut self, threshold: f64) {
+        for i in 0..self.agents.len() {
+            if !self.agents.vacant[i] { self.agents.brains[i].prune(threshold); }
+        }
+    }
+    // Mean share of zero connection weights across living agents
+    pub fn get_avg_sparsity(&self) -> f64 {
+        let living: Vec<f64> = (0..self.agents.len()).filter(|&i| !self.agents.vacant[i]).map(|i| self.agents.brains[i].sparsity()).collect();
+        living.iter().sum::<f64>() / living.len().max(1) as f64
+    }
+    pub fn set_eat_radius(&mut self, val: f64) { self.eat_radius = val; }
+    pub fn set_kill_radius(&mut self, val: f64) { self.kill_radius = val; }
+    // Larger bodies reach further for food but are easier for predators to hit
+    pub fn set_size_scaled_radii(&mut self, enabled: bool) { self.size_scaled_radii = enabled; }
+    pub fn set_predator_pathfinding(&mut self, enabled: bool) { self.predator_pathfinding = enabled; }
+    // Std-dev of Gaussian noise on distance inputs (normalized units) and angles (radians)
+    pub fn set_sensor_noise(&mut self, sigma: f64) { self.sensor_noise = sigma.max(0.0); }
+    // Keys each agent's per-tick random stream (sensor noise, fights, contagion) and reseeds the
+    // shared draws (spawns, mutation, food). The world already built stays as it is; with_seed
+    // builds one from the seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        rng::reseed(Some(seed));
+    }
+    pub fn get_seed(&self) -> u64 { self.seed }
+    // Bit-identical runs across browsers and CPUs: portable trig, and positions/energies snapped
+    // to a fixed grid each tick. The step-time budget is ignored while on, since it reacts to
+    // wall-clock time. Randomness is the seed's business (with_seed / set_seed). The mode is per
+    // thread, so only one simulation per page should use it.
+    pub fn set_fixed_point(&mut self, enabled: bool) {
+        self.fixed_point = enabled;
+        fixed::set_enabled(enabled);
+    }
+    // Absolute heading + home vector; when off those inputs read zero (purely egocentric sensing)
+    pub fn set_compass_sensors(&mut self, enabled: bool) { self.compass_sensors = enabled; }
+    // Larval stage for agents born from now on; turning it off lets current larvae finish growing
+    pub fn set_metamorphosis(&mut self, enabled: bool) { self.metamorphosis = enabled; }
+    pub fn set_cannibalism(&mut self, enabled: bool) { self.cannibalism = enabled; }
+    pub fn set_fatigue(&mut self, enabled: bool) {
+        self.fatigue = enabled;
+        if !enabled { self.agents.fatigue.iter_mut().for_each(|f| *f = 0.0); }
+    }
+    pub fn set_novelty_search(&mut self, enabled: bool) { self.novelty_search = enabled; }
+    pub fn get_novelty_archive_size(&self) -> usize { self.novelty_archive.len() }
+    pub fn set_fitness_config(&mut self, config: &FitnessConfig) { self.fitness = *config; }
+    pub fn get_fitness_config(&self) -> FitnessConfig { self.fitness }
+    // `callback(stats) -> number`; replaces the built-in selection score while set
+    pub fn set_fitness_function(&mut self, callback: js_sys::Function) { self.fitness_fn = Some(callback); }
+    pub fn clear_fitness_function(&mut self) { self.fitness_fn = None; }
+    pub fn set_speciation(&mut self, enabled: bool) {
+        self.speciation = enabled;
+        if enabled { self.species.respeciate(&self.agents.brains, &self.agents.vacant, &mut self.agents.species_ids); }
+    }
+    pub fn set_compatibility_threshold(&mut self, val: f64) { self.species.threshold = val; }
+    pub fn get_species_count(&self) -> usize { if self.speciation { self.species.count() } else { 0 } }
+    pub fn get_species_ids(&self) -> Box<[u32]> { self.agents.species_ids.clone().into_boxed_slice() }
+    pub fn set_food_respawn_policy(&mut self, policy: FoodRespawnPolicy) { self.food_respawn_policy = policy; }
+    pub fn set_food_respawn_delay(&mut self, ticks: u64) { self.food_respawn_delay = ticks; }
+    // `clusters` is only used by the Patchy distribution
+    pub fn set_food_distribution(&mut self, distribution: FoodDistribution, clusters: usize) {
+        self.food_distribution = distribution;
+        if distribution == FoodDistribution::Patchy { self.reset_hotspots(clusters.max(1)); } else { self.hotspots.clear(); }
+    }
+    // Fraction of newly spawned food that is poisonous (existing items keep their flag)
+    pub fn set_poison_ratio(&mut self, ratio: f64) { self.poison_ratio = ratio.clamp(0.0, 1.0); }
+    // Energy lost per tick while standing in another tribe's territory
+    pub fn set_territory_stress(&mut self, cost: f64) { self.territory_stress = cost.max(0.0); }
+    // Constant drift added to every agent and predator, in world units per tick
+    pub fn set_wind(&mut self, dx: f64, dy: f64) { self.wind = (dx, dy); }
+    // Amplitude of the swirling, slowly shifting field layered on top of the constant wind
+    pub fn set_wind_turbulence(&mut self, strength: f64) { self.wind_turbulence = strength.max(0.0); }
+    // 0 makes births instant again
+    pub fn set_gestation_ticks(&mut self, ticks: u64) { self.gestation_ticks = ticks; }
+    // Off = classic fixed-size population where every death is immediately replaced.
+    // Turning it off refills all empty slots with newcomers.
+    pub fn set_dynamic_population(&mut self, enabled: bool, carrying_capacity: usize) {
+        self.dynamic_population = enabled;
+        self.carrying_capacity = carrying_capacity.max(1);
+        if !enabled {
+            while let Some(slot) = self.agents.free_slots.pop() { self.spawn_newcomer(Some(slot)); }
+        }
+    }
+    // Dynamic mode only: energy drained per tick for every neighbor within `radius`
+    pub fn set_density_cost(&mut self, per_neighbor: f64, radius: f64) {
+        self.density_cost = per_neighbor.max(0.0);
+        self.density_radius = radius.max(0.0);
+    }
+    // Ticks an agent can linger at zero energy (still able to eat or be fed) before dying
+    pub fn set_starvation_grace(&mut self, ticks: u32) { self.starvation_grace = ticks; }
+    pub fn get_population(&self) -> usize { self.agents.living() }
+    pub fn get_tick(&self) -> u64 { self.tick }
+    // `callback({ kind: "tribe" | "population", tribe, tick })` on every extinction
+    pub fn set_extinction_callback(&mut self, callback: js_sys::Function) { self.extinction_fn = Some(callback); }
+    pub fn clear_extinction_callback(&mut self) { self.extinction_fn = None; }
+    // On population extinction, reseed from mutated copies of the best genomes seen so far
+    pub fn set_auto_restart(&mut self, enabled: bool) { self.auto_restart = enabled; }
+    pub fn set_scavenger_count(&mut self, count: usize) {
+        self.scavengers.truncate(count);
+        while self.scavengers.len() < count { let s = self.spawn_scavenger(); self.scavengers.push(s); }
+    }
+    // Share of living agents carrying at least one parasite
+    pub fn get_infestation_rate(&self) -> f64 {
+        let living = self.agents.vacant.iter().filter(|v| !**v).count();
+        let infested = self.agents.parasite_loads.iter().zip(&self.agents.vacant).filter(|(l, v)| **l > 0 && !**v).count();
+        infested as f64 / living.max(1) as f64
+    }
+    pub fn get_corpse_count(&self) -> usize { self.corpses.len() }
+    // Hard-coded flocking among tribe-mates on top of the brain's own steering; all zero = off
+    pub fn set_flocking(&mut self, separation: f64, alignment: f64, cohesion: f64) { self.flocking = (separation, alignment, cohesion); }
+    pub fn set_fertility_overlay(&mut self, enabled: bool) { self.show_fertility = enabled; }
+    // Fading lines from both parents to each newborn, showing where reproduction happens
+    pub fn set_gene_flow_overlay(&mut self, enabled: bool) { self.gene_flow = GeneFlow { enabled, ..GeneFlow::default() }; }
+    // Where agents died lately: red for predator kills, amber for starvation
+    pub fn set_death_overlay(&mut self, enabled: bool) { self.show_deaths = enabled; }
+    // Energy and stamina bars over each predator, plus a line to the prey it's chasing
+    pub fn set_predator_overlay(&mut self, enabled: bool) { self.show_predator_state = enabled; }
+    // Predator layer then starvation layer, each row-major over the cells given by get_death_heatmap_dims
+    pub fn get_death_heatmap(&self) -> js_sys::Float32Array { js_sys::Float32Array::from(&self.deaths.flatten()[..]) }
+    // [cols, rows, cell size in world units]
+    pub fn get_death_heatmap_dims(&self) -> Box<[f64]> {
+        Box::new([self.deaths.grid.cols as f64, self.deaths.grid.rows as f64, self.deaths.grid.cell_size])
+    }
     pub fn set_food_count(&mut self, count: usize) {
         let current = self.food.len();
         if count > current {
-            for _ in 0..(count - current) { self.food.push((Math::random() * self.width, Math::random() * self.height)); }
-        } else if count < current { self.food.truncate(count); }
+            for _ in 0..(count - current) { let p = self.new_food_position(); self.add_food(p); }
+        } else if count < current { self.truncate_food(count); }
+    }
+    pub fn resize(&mut self, width: f64, height: f64) {
+        self.width = width; self.height = height;
+        self.screen_w = width; self.screen_h = height;
+        self.rebuild_nav();
+        self.territory = TerritoryMap::new(width, height, TERRITORY_CELL_SIZE);
+        self.fertility = FertilityMap::new(width, height, FERTILITY_CELL_SIZE);
+        self.trails = TrailMap::new(width, height, TRAIL_CELL_SIZE);
+        self.deaths = DeathMap::new(width, height, DEATH_MAP_CELL_SIZE);
+    }
+    // Grows or shrinks the world itself (resize() only changes the bounds); see ResizeMode
+    pub fn resize_world(&mut self, width: f64, height: f64, mode: ResizeMode) -> Result<(), JsValue> {
+        self.resize_world_inner(width, height, mode).map_err(|e| JsValue::from_str(&e))
     }
-    pub fn resize(&mut self, width: f64, height: f64) { self.width = width; self.height = height; }
     pub fn pan(&mut self, dx: f64, dy: f64) { self.view_x += dx / self.zoom; self.view_y += dy / self.zoom; }
-    pub fn zoom_at(&mut self, factor: f64) { self.zoom *= factor; }
-    pub fn get_avg_energy(&self) -> f64 { self.energies.iter().sum::<f64>() / self.energies.len() as f64 }
+    // Keeps the world point under (screen_x, screen_y) fixed while zooming
+    pub fn zoom_at(&mut self, factor: f64, screen_x: f64, screen_y: f64) {
+        let world_x = screen_x / self.zoom + self.view_x;
+        let world_y = screen_y / self.zoom + self.view_y;
+        self.zoom *= factor;
+        self.view_x = world_x - screen_x / self.zoom;
+        self.view_y = world_y - screen_y / self.zoom;
+    }
+
+    // --- CAMERA ---
+    // Screen = (world - view) * zoom, with the view being the world point at the top-left corner
+    pub fn reset_camera(&mut self) { self.view_x = 0.0; self.view_y = 0.0; self.zoom = 1.0; }
+    pub fn zoom_to_fit(&mut self) {
+        self.zoom = (self.screen_w / self.width).min(self.screen_h / self.height);
+        self.view_x = -(self.screen_w / self.zoom - self.width) / 2.0;
+        self.view_y = -(self.screen_h / self.zoom - self.height) / 2.0;
+    }
+    pub fn get_camera(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&CameraState { x: self.view_x, y: self.view_y, zoom: self.zoom }).unwrap()
+    }
+    pub fn set_camera(&mut self, x: f64, y: f64, zoom: f64) {
+        self.view_x = x; self.view_y = y;
+        if zoom > 0.0 { self.zoom = zoom; }
+    }
+    pub fn screen_to_world(&self, x: f64, y: f64) -> Box<[f64]> {
+        Box::new([x / self.zoom + self.view_x, y / self.zoom + self.view_y])
+    }
+    pub fn world_to_screen(&self, x: f64, y: f64) -> Box<[f64]> {
+        Box::new([(x - self.view_x) * self.zoom, (y - self.view_y) * self.zoom])
+    }
+
+    // --- KILL-CAM ---
+    // Kill-cam: centers on the most recent event of `kind` and plays the next `slow_ticks`
+    // ticks at 1/SLOW_MOTION_FACTOR speed. Returns false if no such event has happened yet.
+    pub fn jump_to_last_event(&mut self, kind: EventKind, slow_ticks: u32) -> bool {
+        if !self.focus_event(kind) { return false; }
+        self.slow_motion = slow_ticks;
+        self.slow_motion_frame = 0;
+        true
+    }
+
+    // --- TERRAIN EDITING ---
+    pub fn add_shelter(&mut self, x: f64, y: f64, radius: f64) {
+        self.shelters.push((x, y, radius));
+        self.rebuild_nav();
+    }
+    pub fn remove_shelter_at(&mut self, x: f64, y: f64) {
+        self.shelters.retain(|(sx, sy, sr)| (x - sx).dhypot(y - sy) >= *sr);
+        self.rebuild_nav();
+    }
+    pub fn clear_shelters(&mut self) { self.shelters.clear(); self.rebuild_nav(); }
+    pub fn add_lake(&mut self, x: f64, y: f64, radius: f64) { self.water.push((x, y, radius)); }
+    pub fn clear_lakes(&mut self) { self.water.clear(); }
+    // Walls are WALL_THICKNESS wide; polygons are flat [x0, y0, x1, y1, ...] and must be convex
+    // (either winding). Both block agents, predators, whiskers and predator sight.
+    pub fn add_wall(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) { self.add_barrier(Barrier::wall((x1, y1), (x2, y2))); }
+    pub fn add_polygon(&mut self, points: &[f64]) -> Result<(), JsValue> {
+        self.add_barrier(Barrier::polygon_from_flat(points).map_err(|e| JsValue::from_str(&e))?);
+        Ok(())
+    }
+    pub fn remove_barrier_at(&mut self, x: f64, y: f64) { self.remove_barriers_at(x, y); }
+    pub fn clear_barriers(&mut self) { self.barriers.clear(); self.reindex_barriers(); self.rebuild_nav(); }
+    pub fn get_barriers(&self) -> JsValue { serde_wasm_bindgen::to_value(&self.barriers).unwrap() }
+    // Replaces all terrain with a preset layout and moves everyone into place; see Scenario
+    pub fn load_scenario(&mut self, scenario: Scenario) { self.load_scenario_inner(scenario); }
+    // Lets food and newcomers appear anywhere again (the terrain stays)
+    pub fn clear_scenario(&mut self) { self.food_zones.clear(); self.spawn_zones.clear(); }
+    // Appends a training stage ({ ticks, scenario?, params? }, see Stage); the first one queued
+    // starts right away and each later one once the previous has run its ticks, keeping the
+    // surviving population
+    pub fn queue_scenario(&mut self, config: JsValue) -> Result<(), JsValue> {
+        let stage: Stage = serde_wasm_bindgen::from_value(config)?;
+        self.queue_stage(stage).map_err(|e| JsValue::from_str(&e))
+    }
+    // Drops the stages not started yet; the running one plays out under its settings
+    pub fn clear_scenario_queue(&mut self) { self.pipeline.queued.clear(); }
+    // Number of stages started so far (0 before the first)
+    pub fn get_scenario_stage(&self) -> u32 { self.pipeline.stage }
+    pub fn get_queued_scenarios(&self) -> usize { self.pipeline.queued.len() }
+
+    // --- WORLD GEOMETRY ---
+    // Flat copies in world units for frontends drawing their own overlays and minimaps.
+    // Circles come as [x0, y0, r0, x1, y1, r1, ...], points as [x0, y0, x1, y1, ...].
+    pub fn get_rocks(&self) -> js_sys::Float64Array { js_sys::Float64Array::from(&flatten_circles(&self.rocks)[..]) }
+    pub fn get_mud(&self) -> js_sys::Float64Array { js_sys::Float64Array::from(&flatten_circles(&self.mud)[..]) }
+    // Includes poisoned items, which look the same to agents
+    pub fn get_food_positions(&self) -> js_sys::Float64Array { js_sys::Float64Array::from(&flatten_points(&self.food)[..]) }
+    pub fn get_predator_positions(&self) -> js_sys::Float64Array { js_sys::Float64Array::from(&flatten_points(&self.predators)[..]) }
+
+    // --- BENCHMARK ---
+    // Restarts on the standard benchmark world (see Benchmark); poll get_benchmark_score() while
+    // stepping. Leaves seeded randomness on until the run ends.
+    pub fn start_benchmark(&mut self) { self.start_benchmark_inner(); }
+    pub fn is_benchmark_running(&self) -> bool { self.benchmark.as_ref().is_some_and(|b| b.score.is_none()) }
+    // Food eaten plus survivors once the run is over; undefined before that
+    pub fn get_benchmark_score(&self) -> Option<f64> { self.benchmark.as_ref().and_then(|b| b.score) }
+    pub fn get_benchmark_checkpoints(&self) -> Vec<f64> { self.benchmark.as_ref().map_or(Vec::new(), |b| b.checkpoints.clone()) }
+
+    // Population, tribe mix, food and average energy inside a world-space rectangle
+    pub fn get_region_stats(&self, x: f64, y: f64, w: f64, h: f64) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.region_stats(x, y, w, h)).unwrap()
+    }
+    // Mean/min/max/std of population, average energy, kills and births per tick over the last
+    // `window_ticks` ticks (at most STATS_HISTORY_TICKS)
+    pub fn get_rolling_stats(&self, window_ticks: usize) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.rolling_stats(window_ticks)).unwrap()
+    }
+    // Entity counts and approximate byte sizes per category, to see what to trim on small devices
+    pub fn get_memory_report(&self) -> JsValue { serde_wasm_bindgen::to_value(&self.memory_report()).unwrap() }
+    // Energy in, around and out of the agents' food chain since the books opened (see EnergyLedger);
+    // a growing `unaccounted` means some feature creates or destroys energy off the books
+    pub fn get_energy_ledger(&self) -> JsValue { serde_wasm_bindgen::to_value(&self.energy_ledger()).unwrap() }
+    // Step-time budget in ms (0 = none). Over budget, overlays, voice propagation and then
+    // sensor range are degraded in turn; get_quality_level() reports where things stand.
+    pub fn set_target_step_ms(&mut self, ms: f64) { self.quality.target_ms = ms.max(0.0); }
+    pub fn get_quality_level(&self) -> u32 { self.quality.level }
+    // Fraction of offspring that died before growing out of the juvenile phase
+    pub fn get_juvenile_mortality(&self) -> f64 { self.juvenile_deaths as f64 / self.births.max(1) as f64 }
+    // Successful steals over the whole run, and the fraction of them the victim punished
+    pub fn get_thefts(&self) -> u64 { self.thefts }
+    pub fn get_retaliation_rate(&self) -> f64 { self.retaliations as f64 / self.thefts.max(1) as f64 }
+    // Times a cleaner groomed a predator, over the whole run
+    pub fn get_predator_groomings(&self) -> u64 { self.groomings }
+    // --- SONIFICATION ---
+    // [avg voice of tribe 0..TRIBE_COUNT, kills, births], with the event counts covering
+    // everything since the previous call
+    pub fn get_audio_frame(&mut self) -> js_sys::Float32Array {
+        let mut voice = [0.0f32; TRIBE_COUNT];
+        let mut members = [0u32; TRIBE_COUNT];
+        for i in 0..self.agents.len() {
+            if self.agents.vacant[i] { continue; }
+            voice[self.agents.tribes[i]] += self.agents.voices[i] as f32;
+            members[self.agents.tribes[i]] += 1;
+        }
+        let mut frame: Vec<f32> = voice.iter().zip(members).map(|(v, n)| v / n.max(1) as f32).collect();
+        frame.push(self.audio_kills as f32);
+        frame.push(self.audio_births as f32);
+        self.audio_kills = 0;
+        self.audio_births = 0;
+        js_sys::Float32Array::from(&frame[..])
+    }
+    // Voice bands per tick for one tribe (see VoiceHistory), row-major and oldest first, e.g. to
+    // see whether calling picks up around predator attacks or finds of food
+    pub fn get_voice_spectrogram(&self, tribe: usize) -> js_sys::Float32Array { js_sys::Float32Array::from(&self.voice_spectrogram(tribe)[..]) }
+    // [ticks recorded, bands per tick]
+    pub fn get_voice_spectrogram_dims(&self) -> Box<[f64]> {
+        Box::new([self.voice_history.len() as f64, VOICE_SPECTROGRAM_BINS as f64])
+    }
+
+    pub fn get_avg_energy(&self) -> f64 {
+        let living: Vec<f64> = self.agents.energies.iter().zip(&self.agents.vacant).filter(|(_, v)| !**v).map(|(e, _)| *e).collect();
+        living.iter().sum::<f64>() / living.len().max(1) as f64
+    }
+
+    // Advances by `dt` ms of wall time: one fixed tick per TICK_MS, the remainder carried over to
+    // the next call, so the world runs at the same pace on a 30Hz and a 144Hz display. Speeds and
+    // costs are per tick, so per second they come out the same too. Slow motion stretches each
+    // tick to SLOW_MOTION_FACTOR * TICK_MS. Time for ticks that couldn't run yet (a lock-step peer
+    // is late) stays banked, but anything past MAX_SUBSTEPS ticks (a long stall) is dropped.
+    // Returns how many ticks ran.
+    pub fn step_dt(&mut self, dt: f64) -> u32 {
+        let tick_ms = if self.slow_motion > 0 { TICK_MS * SLOW_MOTION_FACTOR as f64 } else { TICK_MS };
+        self.step_accumulator += dt.max(0.0) / tick_ms * TICK_MS;
+        let due = (self.step_accumulator / TICK_MS) as u32;
+        let ran = self.run_ticks(due.min(MAX_SUBSTEPS), f64::INFINITY);
+        self.step_accumulator -= ran as f64 * TICK_MS;
+        if ran == MAX_SUBSTEPS { self.step_accumulator %= TICK_MS; }
+        ran
+    }
+    // Runs the ticks missed while the page was hidden (browsers pause animation frames there), so
+    // coming back shows a world that kept going. At most CATCH_UP_MAX_TICKS ticks and
+    // CATCH_UP_BUDGET_MS of work; missed time past that is dropped rather than freezing the page.
+    // Returns how many ticks ran.
+    pub fn catch_up(&mut self, ms_elapsed: f64) -> u32 {
+        let due = (ms_elapsed.max(0.0) / TICK_MS).min(CATCH_UP_MAX_TICKS as f64) as u32;
+        self.run_ticks(due, CATCH_UP_BUDGET_MS)
+    }
+    // Fraction of the next tick already accumulated (0..1), for interpolating between ticks when drawing
+    pub fn get_step_alpha(&self) -> f64 { (self.step_accumulator / TICK_MS).min(1.0) }
 
+    // One tick per call, except in slow motion, where only every SLOW_MOTION_FACTOR-th call does anything
     pub fn step(&mut self) {
-        let total_agents = self.positions.len();
+        if self.slow_motion > 0 {
+            self.slow_motion_frame += 1;
+            if !self.slow_motion_frame.is_multiple_of(SLOW_MOTION_FACTOR) { return; }
+        }
+        self.advance();
+    }
+
+    fn advance(&mut self) {
+        self.slow_motion = self.slow_motion.saturating_sub(1);
+        let started = clock::now_ms();
+        let total_agents = self.agents.len();
+        self.tick += 1;
+        self.apply_due_frames();
+
+        self.update_hotspots();
+        self.update_pending_food();
 
         // 1. Refresh Spatial Grid
         self.grid.clear();
         for i in 0..total_agents {
-            if self.energies[i] > 0.0 {
-                self.grid.insert(self.positions[i].0, self.positions[i].1, i);
+            if !self.agents.vacant[i] {
+                self.grid.insert(self.agents.positions[i].0, self.agents.positions[i].1, i);
             }
         }
 
+        self.decay_corpses();
+        self.update_parasites();
+        self.update_scavengers();
+
         // 2. UPDATE PREDATORS
         for i in 0..self.predators.len() {
             let (px, py) = self.predators[i];
-            let mut closest_agent_dist = 999999.0;
-            let mut target_x = px; let mut target_y = py;
+            self.predator_hunts[i] = self.predator_target(i);
+            let (mut target_x, mut target_y) = match self.predator_hunts[i] {
+                Some(t) => t,
+                None => self.predator_wander_point(i),
+            };
 
-            for j in 0..total_agents {
-                if self.energies[j] <= 0.0 { continue; } 
-                let (ax, ay) = self.positions[j];
-                let dist = (px - ax).hypot(py - ay);
-                if dist < closest_agent_dist { closest_agent_dist = dist; target_x = ax; target_y = ay; }
+            // Route around rocks when the straight line to the prey is obstructed
+            let obstructed = self.rocks.iter().chain(&self.shelters).any(|(rx, ry, rr)| segment_hits_circle(px, py, target_x, target_y, *rx, *ry, *rr))
+                || self.barrier_blocks((px, py), (target_x, target_y));
+            if self.predator_pathfinding && obstructed {
+                if let Some((wx, wy)) = self.nav.next_waypoint((px, py), (target_x, target_y)) { target_x = wx; target_y = wy; }
             }
 
-            let speed = self.predator_speed; 
+            let speed = if self.predator_resting[i] {
+                self.predator_stamina[i] += self.stamina_recovery;
+                if self.predator_stamina[i] >= self.stamina_max { self.predator_stamina[i] = self.stamina_max; self.predator_resting[i] = false; }
+                self.predator_speed * self.rest_speed_factor
+            } else {
+                self.predator_stamina[i] -= self.stamina_drain;
+                if self.predator_stamina[i] <= 0.0 { self.predator_stamina[i] = 0.0; self.predator_resting[i] = true; }
+                self.predator_speed
+            };
             let mut dx = target_x - px; let mut dy = target_y - py;
-            let dist = dx.hypot(dy);
+            let dist = dx.dhypot(dy);
             if dist > 0.0 { dx = (dx / dist) * speed; dy = (dy / dist) * speed; }
 
             for k in 0..self.predators.len() {
                 if i == k { continue; }
                 let (ox, oy) = self.predators[k];
-                let sep_dist = (px - ox).hypot(py - oy);
+                let sep_dist = (px - ox).dhypot(py - oy);
                 if sep_dist < 30.0 && sep_dist > 0.0 {
                     let push_x = (px - ox) / sep_dist; let push_y = (py - oy) / sep_dist;
                     dx += push_x * 0.8; dy += push_y * 0.8;
                 }
             }
             
-            let new_px = self.predators[i].0 + dx;
-            let new_py = self.predators[i].1 + dy;
-            let mut hit_rock = false;
-            for (rx, ry, r_rad) in &self.rocks { if (new_px - rx).hypot(new_py - ry) < *r_rad { hit_rock = true; break; } }
-            if !hit_rock { self.predators[i].0 = new_px; self.predators[i].1 = new_py; }
+            let (wx, wy) = self.wind_at(px, py);
+            let new_px = self.predators[i].0 + dx + wx;
+            let new_py = self.predators[i].1 + dy + wy;
+            let mut hit_rock = self.barrier_blocks((px, py), (new_px, new_py));
+            for (rx, ry, r_rad) in self.rocks.iter().chain(&self.shelters) { if (new_px - rx).dhypot(new_py - ry) < *r_rad { hit_rock = true; break; } }
+            if !hit_rock {
+                self.predators[i].0 = new_px; self.predators[i].1 = new_py;
+                if dx != 0.0 || dy != 0.0 { self.predator_angles[i] = dy.datan2(dx); }
+            } else {
+                // Bumped into something: turn away so wandering doesn't get stuck
+                self.predator_angles[i] += PI / 2.0;
+            }
             if self.predators[i].0 < 0.0 { self.predators[i].0 = 0.0; }
             if self.predators[i].0 > self.width { self.predators[i].0 = self.width; }
             if self.predators[i].1 < 0.0 { self.predators[i].1 = 0.0; }
             if self.predators[i].1 > self.height { self.predators[i].1 = self.height; }
+
+            let (px, py) = self.predators[i];
+            if let Some(s) = self.scavengers.iter().position(|s| (s.x - px).dhypot(s.y - py) < self.kill_radius) {
+                self.predator_energies[i] = (self.predator_energies[i] + SCAVENGER_KILL_ENERGY).min(ENERGY_CAP);
+                self.scavengers[s] = self.spawn_scavenger();
+            }
         }
 
+        self.territory.decay(TERRITORY_DECAY);
+        self.trails.decay(TRAIL_DECAY);
+        self.deaths.decay(DEATH_MAP_DECAY);
+
         // 3. UPDATE AGENTS
         for i in 0..total_agents {
-            let (my_x, my_y) = self.positions[i];
-            let my_angle = self.angles[i];
+            // Slot reserved for an offspring that hasn't been born yet
+            if self.agents.vacant[i] { continue; }
+            if self.agents.gestations[i].as_ref().is_some_and(|g| self.tick >= g.due) { self.deliver(i); }
+            let (my_x, my_y) = self.agents.positions[i];
+            let my_angle = self.agents.angles[i];
+            let tribe_params = self.tribe_params[self.agents.tribes[i]];
+            // Agent-local randomness comes from its own stream, independent of processing order
+            let mut rng = rng::Stream::new(self.seed, self.agents.ids[i], self.tick);
 
             let mut closest_food_dist = 9999.0;
             let mut food_angle_diff = 0.0;
             let mut closest_food_index = 0; 
-            for (idx, (fx, fy)) in self.food.iter().enumerate() {
-                let dx = fx - my_x; let dy = fy - my_y;
-                let dist = dx.hypot(dy);
-                if dist < closest_food_dist {
-                    closest_food_dist = dist; closest_food_index = idx;
-                    food_angle_diff = dy.atan2(dx) - my_angle;
-                }
+            let sensor_range = if self.quality.full_sensor_range() { f64::INFINITY } else { DEGRADED_SENSOR_RANGE };
+            if let Some((idx, dist)) = self.nearest_food(my_x, my_y, sensor_range).filter(|(_, d)| *d < closest_food_dist) {
+                let (fx, fy) = self.food[idx];
+                closest_food_dist = dist; closest_food_index = idx;
+                food_angle_diff = (fy - my_y).datan2(fx - my_x) - my_angle;
             }
 
             let mut closest_friend_dist = 9999.0;
+            let mut closest_rival_dist = 9999.0;
             let mut hearing_vol = 0.0; 
+            let mut crowd = 0;
             
             let neighbors = self.grid.query(my_x, my_y);
             
             for &j in &neighbors {
                 if i == j { continue; }
-                let (fx, fy) = self.positions[j];
-                let dist = (fx - my_x).hypot(fy - my_y);
+                let (fx, fy) = self.agents.positions[j];
+                let dist = (fx - my_x).dhypot(fy - my_y);
                 if dist < closest_friend_dist { closest_friend_dist = dist; }
-                if dist < 100.0 { hearing_vol += self.voices[j] * (1.0 - dist/100.0); }
+                if dist < closest_rival_dist && self.agents.tribes[j] != self.agents.tribes[i] { closest_rival_dist = dist; }
+                if dist < self.density_radius { crowd += 1; }
+                if dist < HEARING_RADIUS && self.quality.voices() { hearing_vol += self.agents.voices[j] * (1.0 - dist/HEARING_RADIUS); }
             }
 
             let mut closest_pred_dist = 9999.0;
@@ -230,155 +1228,949 @@ impl Simulation {
             let mut closest_pred_index = 0; 
             for (idx, (px, py)) in self.predators.iter().enumerate() {
                 let dx = px - my_x; let dy = py - my_y;
-                let dist = dx.hypot(dy);
+                if dx.abs() > sensor_range || dy.abs() > sensor_range { continue; }
+                let dist = dx.dhypot(dy);
                 if dist < closest_pred_dist {
                     closest_pred_dist = dist; closest_pred_index = idx;
-                    pred_angle_diff = dy.atan2(dx) - my_angle;
+                    pred_angle_diff = dy.datan2(dx) - my_angle;
                 }
             }
 
-            let check_obstacle = |angle_offset: f64| -> f64 {
-                let angle = my_angle + angle_offset;
-                let rx = my_x + angle.cos() * WHISKER_LEN;
-                let ry = my_y + angle.sin() * WHISKER_LEN;
-                if rx < 0.0 || rx > self.width || ry < 0.0 || ry > self.height { return 1.0; }
-                for (rock_x, rock_y, rock_r) in &self.rocks { if (rx - rock_x).hypot(ry - rock_y) < *rock_r { return 1.0; } }
-                0.0
-            };
-            let wall_l = check_obstacle(-0.78); 
-            let wall_c = check_obstacle(0.0);
-            let wall_r = check_obstacle(0.78); 
+            let check_obstacle = |angle_offset: f64| -> f64 { if self.whisker_hit(my_x, my_y, my_angle + angle_offset) { 1.0 } else { 0.0 } };
+            let wall_l = check_obstacle(WHISKER_ANGLES[0]); 
+            let wall_c = check_obstacle(WHISKER_ANGLES[1]);
+            let wall_r = check_obstacle(WHISKER_ANGLES[2]); 
             let mut in_mud = 0.0;
-            for (mx, my, mr) in &self.mud { if (my_x - mx).hypot(my_y - my) < *mr { in_mud = 1.0; break; } }
-
-            // MERGED: Using the 13-input logic from the second block (includes Cosine)
-            let inputs = [
-                (closest_food_dist / self.width).min(1.0),
-                food_angle_diff.sin(), 
-                food_angle_diff.cos(), // NEW: Front/Back distinction
-                (closest_pred_dist / self.width).min(1.0),
-                pred_angle_diff.sin(),
-                pred_angle_diff.cos(), // NEW: Front/Back distinction
-                self.energies[i] / 100.0,
-                (closest_friend_dist / 200.0).min(1.0),
+            for (mx, my, mr) in &self.mud { if (my_x - mx).dhypot(my_y - my) < *mr { in_mud = 1.0; break; } }
+
+            // Imperfect perception: jitter distances and angles before they reach the brain
+            let sigma = self.sensor_noise;
+            let mut noise = |v: f64| if sigma > 0.0 { v + rng.gaussian() * sigma } else { v };
+            let food_angle_diff = noise(food_angle_diff);
+            let pred_angle_diff = noise(pred_angle_diff);
+            // Beyond the tribe's vision range things read as "nothing there"
+            let seen = |dist: f64, angle: f64| if dist <= tribe_params.vision_range { (dist, angle) } else { (9999.0, 0.0) };
+            let (seen_food_dist, food_angle_diff) = seen(closest_food_dist, food_angle_diff);
+            let (seen_pred_dist, pred_angle_diff) = seen(closest_pred_dist, pred_angle_diff);
+
+            // Temporal context: energy trend and bearing back to the last meal
+            let slot = (self.tick as usize) % ENERGY_MEMORY_TICKS;
+            let energy_trend = ((self.agents.energies[i] - self.agents.energy_history[i][slot]) / FOOD_ENERGY).clamp(-1.0, 1.0);
+            self.agents.energy_history[i][slot] = self.agents.energies[i];
+            let (meal_sin, meal_cos) = match self.agents.last_meals[i] {
+                Some((mx, my)) => { let a = (my - my_y).datan2(mx - my_x) - my_angle; (a.dsin(), a.dcos()) }
+                None => (0.0, 0.0),
+            };
+
+            // Smell: only up close can poison be told apart (+1 poison, -1 wholesome, 0 nothing near)
+            let smell = if closest_food_dist < SMELL_RADIUS {
+                if self.food_poison[closest_food_index] { 1.0 } else { -1.0 }
+            } else { 0.0 };
+
+            // Allocentric context: compass heading and the way back to where this agent was born
+            let (compass_sin, compass_cos, home_sin, home_cos, home_dist) = if self.compass_sensors {
+                let (hx, hy) = self.agents.spawn_points[i];
+                let home_angle = (hy - my_y).datan2(hx - my_x) - my_angle;
+                let home_dist = ((hx - my_x).dhypot(hy - my_y) / self.width).min(1.0);
+                (my_angle.dsin(), my_angle.dcos(), home_angle.dsin(), home_angle.dcos(), home_dist)
+            } else {
+                (0.0, 0.0, 0.0, 0.0, 0.0)
+            };
+
+            // Territory: +1 own tribe's ground, -1 a rival's, 0 unclaimed
+            let tribe = self.agents.tribes[i];
+            let territory = match self.territory.owner(my_x, my_y) {
+                Some(owner) => if owner == tribe { 1.0 } else { -1.0 },
+                None => 0.0,
+            };
+
+            // Thirst: how dry this agent is and how far the nearest lakeshore is
+            let thirst = self.agents.thirsts[i];
+            let water_dist = self.water.iter()
+                .map(|(wx, wy, wr)| ((wx - my_x).dhypot(wy - my_y) - wr).max(0.0))
+                .fold(self.width, f64::min);
+
+            let larval = self.update_stage(i);
+
+            // Temperament: fearful agents read predators as nearer, aggressive ones rivals
+            let traits = &self.agents.traits[i];
+            let (fear, aggression) = (traits.fear, traits.aggression);
+
+            // Length is checked against BRAIN_INPUTS at compile time; new sensors go at the end
+            let mut inputs: [f64; BRAIN_INPUTS] = [
+                noise((seen_food_dist / self.width).min(1.0)).clamp(0.0, 1.0),
+                food_angle_diff.dsin(), 
+                food_angle_diff.dcos(), // NEW: Front/Back distinction
+                noise((seen_pred_dist / self.width / fear).min(1.0)).clamp(0.0, 1.0),
+                pred_angle_diff.dsin(),
+                pred_angle_diff.dcos(), // NEW: Front/Back distinction
+                self.agents.energies[i] / 100.0,
+                noise((closest_friend_dist / 200.0).min(1.0)).clamp(0.0, 1.0),
                 wall_l, wall_c, wall_r,
                 hearing_vol.min(1.0), 
-                in_mud                
+                in_mud,
+                energy_trend,
+                meal_sin, meal_cos,
+                compass_sin, compass_cos,
+                home_sin, home_cos, home_dist,
+                smell,
+                territory,
+                thirst,
+                noise((water_dist / self.width).min(1.0)).clamp(0.0, 1.0),
+                self.agents.stomachs[i] / STOMACH_CAPACITY,
+                if self.agents.carrying[i].is_some() { 1.0 } else { 0.0 },
+                ((STARVING_THRESHOLD - self.agents.energies[i]) / STARVING_THRESHOLD).clamp(0.0, 1.0),
+                noise((closest_rival_dist / 200.0 / aggression).min(1.0)).clamp(0.0, 1.0),
+                if larval { 1.0 } else { 0.0 },
+                self.agents.fatigue[i],
             ];
+            // Sensor groups evolved away read as silence
+            let sensor_mask = self.agents.traits[i].sensor_mask();
+            for (on, group) in sensor_mask.iter().zip(SENSOR_GROUP_INPUTS) {
+                if !on { group.iter().for_each(|&k| inputs[k] = 0.0); }
+            }
             
-            let outputs = self.brains[i].process(&inputs);
-            let turn_force = outputs[0] * TURN_SPEED; 
-            let mut speed = (outputs[1] + 1.0) * AGENT_SPEED_MODIFIER; 
-            self.voices[i] = outputs[2].max(0.0);
+            let mut outputs = self.agents.brains[i].process(&inputs);
+            let tiredness = (self.agents.fatigue[i] - FATIGUE_THRESHOLD) / (1.0 - FATIGUE_THRESHOLD);
+            if tiredness > 0.0 {
+                outputs.iter_mut().for_each(|o| *o = (*o + rng.gaussian() * tiredness * FATIGUE_NOISE).clamp(-1.0, 1.0));
+            }
+            // Controlled agents still "think" (for the inspector) but obey the player / JS policy
+            if let Some(controller) = self.external_controllers.get(&self.agents.ids[i]) {
+                let arg = js_sys::Float64Array::from(&inputs[..]);
+                match controller.call1(&JsValue::NULL, &arg) {
+                    Ok(result) => {
+                        let values = js_sys::Float64Array::new(&result).to_vec();
+                        for (k, o) in outputs.iter_mut().enumerate() { *o = values.get(k).copied().filter(|v| v.is_finite()).unwrap_or(0.0).clamp(-1.0, 1.0); }
+                    }
+                    Err(_) => self.log_buffer.push(format!("⚠️ Controller for agent {} threw, using its brain", self.agents.ids[i])),
+                }
+            }
+            if self.possessed == Some((i, self.agents.ids[i])) { outputs = self.manual_controls; }
+            if let Some(buf) = &mut self.observations { observations::record(buf, self.tick, self.agents.ids[i], &inputs, &outputs); }
+            if self.is_tracked(i) {
+                let (position, energy) = (self.agents.positions[i], self.agents.energies[i]);
+                if let Some(t) = &mut self.track { t.record(self.tick, position, energy, &outputs); }
+            }
+            // Torpor: stay put and idle the metabolism
+            let torpid = outputs[4] > 0.5;
+            self.agents.torpid[i] = torpid;
+            // Burrowing: dig into mud and sit out of predators' reach, surfacing when the output
+            // drops or time runs out
+            let burrowed = self.update_burrow(i, outputs[6] > 0.5, in_mud > 0.0);
+            let still = torpid || burrowed;
+            let turn_force = if still { 0.0 } else { outputs[0] * TURN_SPEED }; 
+            let mut speed = if still { 0.0 } else { (outputs[1] + 1.0) * AGENT_SPEED_MODIFIER * tribe_params.speed_modifier }; 
+            if let Some(m) = Intervention::active(&self.handicaps[self.agents.tribes[i]], self.tick) { speed *= m; }
+            self.agents.voices[i] = outputs[2].max(0.0);
 
             if in_mud > 0.0 { speed *= 0.3; }
-            self.angles[i] += turn_force;
-            let vx = self.angles[i].cos() * speed;
-            let vy = self.angles[i].sin() * speed;
-            let new_x = my_x + vx; let new_y = my_y + vy;
+            let photosynthesis = self.agents.traits[i].photosynthesis;
+            speed *= 1.0 - photosynthesis * PHOTOSYNTHESIS_SPEED_PENALTY;
+            let pregnant = self.agents.gestations[i].is_some();
+            if pregnant { speed *= GESTATION_SPEED_FACTOR; }
+            if self.agents.carrying[i].is_some() { speed *= CARRY_SPEED_FACTOR; }
+            if self.agents.energies[i] < STARVING_THRESHOLD { speed *= STARVING_SPEED_FACTOR; }
+            let juvenile = self.is_juvenile(i);
+            if juvenile { speed *= JUVENILE_SPEED_FACTOR; }
+            if larval { speed *= LARVA_SPEED_FACTOR; }
+            self.agents.angles[i] += turn_force;
+            // Well-trodden paths are quicker going, at no extra energy cost
+            let trail_boost = 1.0 + self.trails.at(my_x, my_y) * TRAIL_SPEED_BONUS;
+            let vx = self.agents.angles[i].dcos() * speed * trail_boost;
+            let vy = self.agents.angles[i].dsin() * speed * trail_boost;
+            let (wx, wy) = self.wind_at(my_x, my_y);
+            let (fx, fy) = if still { (0.0, 0.0) } else { self.flocking_force(i, &neighbors) };
+            let new_x = my_x + vx + wx + fx; let new_y = my_y + vy + wy + fy;
 
             let mut hit_rock = false;
-            for (rx, ry, rr) in &self.rocks { if (new_x - rx).hypot(new_y - ry) < *rr { hit_rock = true; break; } }
-            if !hit_rock { self.positions[i] = (new_x, new_y); }
+            if !self.is_free(new_x, new_y) || self.barrier_blocks((my_x, my_y), (new_x, new_y)) { hit_rock = true; }
+            if !hit_rock { self.agents.positions[i] = (new_x, new_y); }
+
+            if self.agents.positions[i].0 < 0.0 { self.agents.positions[i].0 = 0.0; }
+            if self.agents.positions[i].0 > self.width { self.agents.positions[i].0 = self.width; }
+            if self.agents.positions[i].1 < 0.0 { self.agents.positions[i].1 = 0.0; }
+            if self.agents.positions[i].1 > self.height { self.agents.positions[i].1 = self.height; }
+            self.agents.distances[i] += (self.agents.positions[i].0 - my_x).dhypot(self.agents.positions[i].1 - my_y);
+            self.agents.voice_totals[i] += self.agents.voices[i];
+            if speed > 0.0 { self.trails.wear(self.agents.positions[i].0, self.agents.positions[i].1, TRAIL_WEAR); }
+            self.territory.deposit(self.agents.positions[i].0, self.agents.positions[i].1, tribe, TERRITORY_DEPOSIT);
 
-            if self.positions[i].0 < 0.0 { self.positions[i].0 = 0.0; }
-            if self.positions[i].0 > self.width { self.positions[i].0 = self.width; }
-            if self.positions[i].1 < 0.0 { self.positions[i].1 = 0.0; }
-            if self.positions[i].1 > self.height { self.positions[i].1 = self.height; }
+            // Wading drinks and washes parasites off; tribe-mates can groom each other, and small
+            // agents can groom a predator for food and a truce
+            if self.in_water(self.agents.positions[i].0, self.agents.positions[i].1) {
+                self.agents.parasite_loads[i] = 0;
+                self.agents.thirsts[i] = (self.agents.thirsts[i] - DRINK_RATE).max(0.0);
+            } else {
+                self.agents.thirsts[i] = (self.agents.thirsts[i] + THIRST_RATE).min(1.0);
+            }
+            let groomed = outputs[3] > 0.5 && (self.groom(i, &neighbors) || self.clean_predator(i, closest_pred_index, closest_pred_dist));
+            self.spread_parasites(i, &neighbors, &mut rng);
+            if outputs[7] > 0.5 && !still { self.steal(i, &neighbors, &mut rng); }
+            if self.cannibalism && !still && !larval { self.cannibalize(i, &neighbors); }
 
             let mut cost = speed * MOVE_COST;
             if in_mud > 0.0 { cost *= 3.0; } 
-            cost += self.voices[i] * 0.1;   
-            self.energies[i] -= cost;
-
-            if closest_food_dist < EAT_RADIUS {
-                 self.energies[i] += FOOD_ENERGY; 
-                 if self.energies[i] > ENERGY_CAP { self.energies[i] = ENERGY_CAP; } 
-                 self.food[closest_food_index] = (Math::random() * self.width, Math::random() * self.height);
+            cost += self.agents.voices[i] * 0.1;   
+            cost += self.agents.traits[i].combat * COMBAT_UPKEEP;
+            cost += sensor_mask.iter().zip(SENSOR_GROUP_COSTS).filter(|(on, _)| **on).map(|(_, c)| c).sum::<f64>();
+            if territory < 0.0 { cost += self.territory_stress; }
+            if pregnant { cost += GESTATION_UPKEEP; }
+            cost += self.agents.parasite_loads[i] as f64 * PARASITE_DRAIN;
+            if groomed { cost += GROOM_COST; }
+            cost += (self.agents.thirsts[i] - THIRST_THRESHOLD).max(0.0) * DEHYDRATION_COST;
+            // Crowding: lets a dynamic population settle below its cap instead of piling up against it
+            if self.dynamic_population { cost += crowd as f64 * self.density_cost; }
+            if torpid { cost *= TORPOR_METABOLISM; }
+            if burrowed { cost += BURROW_COST; }
+            self.agents.energies[i] -= cost;
+            self.ledger.metabolism += cost;
+            if self.fatigue { self.update_fatigue(i, speed); }
+            // Larvae graze the nutrients in mud
+            if larval && in_mud > 0.0 { self.gain_energy(i, LARVA_MUD_FEED); self.ledger.grazing += LARVA_MUD_FEED; }
+            // Photosynthesis: sitting still in open ground (no mud, no shelter canopy) soaks up light
+            let (px, py) = self.agents.positions[i];
+            if speed < PHOTOSYNTHESIS_STILL_SPEED && !burrowed && in_mud == 0.0 && !self.in_shelter(px, py) {
+                self.gain_energy(i, photosynthesis * PHOTOSYNTHESIS_RATE);
+                self.ledger.sunlight += photosynthesis * PHOTOSYNTHESIS_RATE;
             }
 
-            if closest_pred_dist < PREDATOR_KILL_RADIUS {
-                if self.energies[i] > WARRIOR_THRESHOLD {
-                    self.predators[closest_pred_index] = (Math::random() * self.width, Math::random() * self.height);
-                    self.energies[i] -= BATTLE_COST;
-                    // MERGED: Log the kill
-                    self.log_buffer.push(format!("⚔️ Agent {} Killed a Predator!", i));
-                } else {
-                    self.energies[i] = -10.0; 
+            // Juveniles get fed by their mother while she's close by, as much as her care gene allows
+            if juvenile {
+                if let Some(parent_id) = self.lineage.parent_of(self.agents.ids[i]) {
+                    let (x, y) = self.agents.positions[i];
+                    let parent = neighbors.iter().copied().find(|&j| self.agents.ids[j] == parent_id && !self.agents.vacant[j]);
+                    if let Some(p) = parent.filter(|&p| (self.agents.positions[p].0 - x).dhypot(self.agents.positions[p].1 - y) < FEED_RADIUS) {
+                        if self.agents.energies[p] > PARENTAL_FEED_MIN {
+                            let amount = self.agents.traits[p].care * PARENTAL_FEED_RATE;
+                            self.agents.energies[p] -= amount;
+                            self.gain_energy(i, amount);
+                            self.ledger.parental += amount;
+                        }
+                    }
                 }
             }
 
-            if self.energies[i] <= 0.0 {
-                let mut p1_idx = 0; let mut max_e1 = -1.0;
-                for _ in 0..5 {
-                    let r = (Math::random() * total_agents as f64) as usize;
-                    if r != i && self.energies[r] > max_e1 { max_e1 = self.energies[r]; p1_idx = r; }
-                }
-                let mut p2_idx = 0; let mut max_e2 = -1.0;
-                for _ in 0..5 {
-                    let r = (Math::random() * total_agents as f64) as usize;
-                    if r != i && r != p1_idx && self.energies[r] > max_e2 { max_e2 = self.energies[r]; p2_idx = r; }
+            let size_scale = if self.size_scaled_radii { self.agents.traits[i].body_size } else { 1.0 };
+            // Meals go to the stomach first; a full stomach can't take another one
+            let stomach_room = STOMACH_CAPACITY - self.agents.stomachs[i];
+            if !larval && closest_food_dist < self.eat_radius * size_scale && stomach_room >= FOOD_ENERGY {
+                 if self.food_poison[closest_food_index] {
+                     self.agents.energies[i] -= POISON_DAMAGE;
+                     self.ledger.damage += POISON_DAMAGE;
+                 } else {
+                     self.agents.last_meals[i] = Some(self.food[closest_food_index]);
+                     self.agents.stomachs[i] += FOOD_ENERGY;
+                     self.ledger.eaten += FOOD_ENERGY;
+                     self.meals += 1;
+                 }
+                 self.consume_food(closest_food_index);
+            }
+            // Carrion is up for grabs for agents as well as scavengers
+            if let Some((c, d)) = self.nearest_corpse(self.agents.positions[i].0, self.agents.positions[i].1) {
+                let room = STOMACH_CAPACITY - self.agents.stomachs[i];
+                if d < self.eat_radius * size_scale && room > 0.0 {
+                    let taken = self.eat_corpse(c, FOOD_ENERGY.min(room));
+                    self.agents.stomachs[i] += taken;
+                    self.ledger.carrion += taken;
                 }
+            }
+            // Grab: hold on to a food item while the output stays high, drop it where it's released
+            let (ax, ay) = self.agents.positions[i];
+            match (outputs[5] > 0.5, self.agents.carrying[i]) {
+                (true, None) => self.agents.carrying[i] = self.grab_food(ax, ay, self.eat_radius * size_scale),
+                (false, Some(poison)) => { self.place_food((ax, ay), poison); self.agents.carrying[i] = None; }
+                _ => {}
+            }
 
-                if max_e1 > self.reproduction_threshold && max_e2 > self.reproduction_threshold { 
-                    let mut new_brain = self.brains[p1_idx].crossover(&self.brains[p2_idx]);
-                    new_brain = new_brain.mutate(self.mutation_rate);
-                    self.brains[i] = new_brain;
-                    self.colors[i] = self.colors[p1_idx].clone(); 
-                    let (px, py) = self.positions[p1_idx];
-                    self.positions[i] = (px + (Math::random()-0.5)*10.0, py + (Math::random()-0.5)*10.0);
-                    self.energies[i] = 60.0; 
-                    self.energies[p1_idx] -= 20.0; 
-                    self.energies[p2_idx] -= 20.0; 
+            // Digestion trickles energy in, and the droppings fertilize the ground
+            let digested = self.agents.stomachs[i].min(DIGEST_RATE);
+            self.agents.stomachs[i] -= digested;
+            self.gain_energy(i, digested);
+            self.ledger.digested += digested;
+            if let Some(bonus) = Intervention::active(&self.boosts[self.agents.tribes[i]], self.tick) {
+                self.gain_energy(i, bonus);
+                self.ledger.rewards += bonus;
+            }
+            self.fertility.add(self.agents.positions[i].0, self.agents.positions[i].1, digested / FOOD_ENERGY * FERTILITY_DROPPING);
+
+            let mut killed = false;
+            let sheltered = self.in_shelter(self.agents.positions[i].0, self.agents.positions[i].1);
+            if closest_pred_dist < self.kill_radius * size_scale && !sheltered && !self.burrowed(i) && !self.tolerated(i, closest_pred_index) {
+                let p = closest_pred_index;
+                if self.agents.energies[i] > WARRIOR_THRESHOLD && !larval {
+                    self.note_event(EventKind::Fight, self.agents.positions[i]);
+                    // Warriors stand and fight: odds scale with energy and the combat gene
+                    let win_chance = (self.agents.energies[i] / ENERGY_CAP).min(1.0) * self.agents.traits[i].combat;
+                    if rng.next_f64() < win_chance {
+                        self.agents.energies[i] -= BATTLE_COST;
+                        self.ledger.damage += BATTLE_COST;
+                        self.predator_energies[p] -= PREDATOR_FIGHT_DAMAGE;
+                        if self.predator_energies[p] <= 0.0 {
+                            // A territorial predator's successor takes over the same patch
+                            self.predators[p] = if self.predator_territory > 0.0 { self.predator_homes[p] } else { self.random_open_position() };
+                            self.predator_energies[p] = PREDATOR_STARTING_ENERGY;
+                            self.predator_stamina[p] = self.stamina_max;
+                            self.predator_resting[p] = false;
+                            self.agents.kill_counts[i] += 1;
+                            self.log_buffer.push(format!("⚔️ Agent {} Killed a Predator!", self.agents.ids[i]));
+                            self.audio_kills += 1;
+                            self.kills += 1;
+                            self.note_event(EventKind::Kill, self.agents.positions[i]);
+                        } else {
+                            // Driven off: knock the predator back out of reach
+                            let (px, py) = self.predators[p];
+                            let (ax, ay) = self.agents.positions[i];
+                            let d = (px - ax).dhypot(py - ay).max(0.001);
+                            self.predators[p] = (
+                                (px + (px - ax) / d * FIGHT_KNOCKBACK).clamp(0.0, self.width),
+                                (py + (py - ay) / d * FIGHT_KNOCKBACK).clamp(0.0, self.height),
+                            );
+                            self.log_buffer.push(format!("🛡️ Agent {} Fought Off Predator {}", self.agents.ids[i], p));
+                        }
+                    } else {
+                        self.ledger.predation += self.agents.energies[i];
+                        self.agents.energies[i] = -10.0;
+                        killed = true;
+                        self.audio_kills += 1;
+                        self.kills += 1;
+                        self.predator_energies[p] = (self.predator_energies[p] + PREDATOR_KILL_ENERGY).min(ENERGY_CAP);
+                        self.log_buffer.push(format!("🩸 Predator {} Won a Fight Against Agent {}", p, self.agents.ids[i]));
+                    }
                 } else {
-                    self.brains[i] = Brain::new();
-                    self.positions[i] = (Math::random() * self.width, Math::random() * self.height);
-                    self.energies[i] = 100.0;
-                    self.voices[i] = 0.0;
+                    self.ledger.predation += self.agents.energies[i];
+                    self.agents.energies[i] = -10.0;
+                    killed = true;
+                    self.audio_kills += 1;
+                    self.kills += 1;
+                    self.predator_energies[p] = (self.predator_energies[p] + PREDATOR_KILL_ENERGY).min(ENERGY_CAP);
                 }
+                if killed { self.note_event(EventKind::Kill, self.agents.positions[i]); }
+            }
+
+            // Running dry isn't instantly fatal: the agent lingers at zero for a grace period
+            if self.agents.energies[i] <= 0.0 && !killed {
+                self.ledger.adjustments -= self.agents.energies[i];
+                self.agents.energies[i] = 0.0;
+                self.agents.starving_ticks[i] += 1;
+            } else {
+                self.agents.starving_ticks[i] = 0;
             }
+            if killed || self.agents.starving_ticks[i] > self.starvation_grace {
+                let cause = if killed { DeathCause::Predator } else { DeathCause::Starvation };
+                self.deaths.add(self.agents.positions[i].0, self.agents.positions[i].1, cause);
+                self.die(i);
+            } else if self.dynamic_population {
+                self.try_mate(i);
+            }
+        }
+
+        // 4. Forget ancestors with no living descendants
+        if self.tick.is_multiple_of(LINEAGE_PRUNE_INTERVAL) { let living = self.agents.living_ids(); self.lineage.prune(&living); }
+        if self.speciation && self.tick.is_multiple_of(SPECIATION_INTERVAL) {
+            self.species.respeciate(&self.agents.brains, &self.agents.vacant, &mut self.agents.species_ids);
         }
+        self.record_keyframe();
+        self.record_stats();
+        self.record_voices();
+        self.update_benchmark();
+        self.update_pipeline();
+        if self.fixed_point { self.snap_to_grid(); } else { self.adapt_quality(clock::now_ms() - started); }
+        self.check_extinction();
+        self.update_curriculum();
+    }
+
+    pub fn draw(&self, context: &web_sys::CanvasRenderingContext2d) -> Result<(), JsValue> {
+        self.render(context, self.screen_w, self.screen_h, self.view_x, self.view_y, self.zoom)
     }
 
-    pub fn draw(&self, context: &web_sys::CanvasRenderingContext2d) {
-        context.set_fill_style(&JsValue::from_str("#111"));
-        context.fill_rect(0.0, 0.0, self.width, self.height);
+    // --- SCREENSHOTS ---
+    // Renders the whole world, fitted into a width x height image, independent of the live camera
+    pub fn render_to_image_data(&self, width: u32, height: u32) -> Result<Clamped<Vec<u8>>, JsValue> {
+        let document = web_sys::window().and_then(|w| w.document()).ok_or("no document available")?;
+        let canvas: web_sys::HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+        canvas.set_width(width);
+        canvas.set_height(height);
+        let context: web_sys::CanvasRenderingContext2d = canvas.get_context("2d")?.ok_or("no 2d context")?.dyn_into()?;
+
+        let (w, h) = (width as f64, height as f64);
+        let zoom = (w / self.width).min(h / self.height);
+        // Center the world inside the image
+        let view_x = -(w / zoom - self.width) / 2.0;
+        let view_y = -(h / zoom - self.height) / 2.0;
+        self.render(&context, w, h, view_x, view_y, zoom)?;
+        Ok(context.get_image_data(0.0, 0.0, w, h)?.data())
+    }
+}
+
+impl Simulation {
+    fn render(&self, context: &web_sys::CanvasRenderingContext2d, screen_w: f64, screen_h: f64, view_x: f64, view_y: f64, zoom: f64) -> Result<(), JsValue> {
+        context.set_fill_style_str(&self.theme.background);
+        context.fill_rect(0.0, 0.0, screen_w, screen_h);
         context.save();
-        context.scale(self.zoom, self.zoom).unwrap();
-        context.translate(-self.view_x, -self.view_y).unwrap();
-        context.set_stroke_style(&JsValue::from_str("#222"));
+        context.scale(zoom, zoom)?;
+        context.translate(-view_x, -view_y)?;
+        context.set_stroke_style_str("#222");
         context.set_line_width(5.0);
         context.stroke_rect(0.0, 0.0, self.width, self.height);
+        // Only what overlaps the viewport (plus `margin`) gets drawn, which is what keeps big worlds cheap
+        let (x1, y1) = (view_x + screen_w / zoom, view_y + screen_h / zoom);
+        let visible = |x: f64, y: f64, margin: f64| x + margin >= view_x && x - margin <= x1 && y + margin >= view_y && y - margin <= y1;
+        let visible_cell = |grid: &CellGrid, c: usize| { let (x, y) = grid.corner(c); visible(x + grid.cell_size * 0.5, y + grid.cell_size * 0.5, grid.cell_size) };
+
+        context.set_fill_style_str(&self.theme.mud); 
+        for (mx, my, mr) in self.mud.iter().filter(|c| visible(c.0, c.1, c.2)) { context.begin_path(); context.arc(*mx, *my, *mr, 0.0, TAU)?; context.fill(); }
+        context.set_fill_style_str("#1e4f8a");
+        for (wx, wy, wr) in self.water.iter().filter(|c| visible(c.0, c.1, c.2)) { context.begin_path(); context.arc(*wx, *wy, *wr, 0.0, TAU)?; context.fill(); }
+        if self.show_fertility && self.quality.overlays() {
+            let cell = self.fertility.grid.cell_size;
+            context.set_fill_style_str("#6b8e23");
+            for (c, f) in self.fertility.cells.iter().enumerate() {
+                if *f <= 0.0 || !visible_cell(&self.fertility.grid, c) { continue; }
+                context.set_global_alpha(f / FERTILITY_MAX * 0.4);
+                let (x, y) = self.fertility.grid.corner(c);
+                context.fill_rect(x, y, cell, cell);
+            }
+            context.set_global_alpha(1.0);
+        }
+        if self.quality.overlays() {
+            let cell = self.trails.grid.cell_size;
+            context.set_fill_style_str("#c8b88a");
+            for (c, w) in self.trails.cells.iter().enumerate() {
+                if *w < 0.05 || !visible_cell(&self.trails.grid, c) { continue; }
+                context.set_global_alpha(w * 0.15);
+                let (x, y) = self.trails.grid.corner(c);
+                context.fill_rect(x, y, cell, cell);
+            }
+            context.set_global_alpha(1.0);
+            let cell = self.territory.grid.cell_size;
+            for c in (0..self.territory.grid.cell_count()).filter(|&c| visible_cell(&self.territory.grid, c)) {
+                if let Some((tribe, strength)) = self.territory.owner_of_cell(c) {
+                    context.set_fill_style_str(&self.palette[tribe]);
+                    context.set_global_alpha(strength * 0.12);
+                    let (x, y) = self.territory.grid.corner(c);
+                    context.fill_rect(x, y, cell, cell);
+                }
+            }
+            context.set_global_alpha(1.0);
+        }
+        if self.show_deaths && self.quality.overlays() {
+            let cell = self.deaths.grid.cell_size;
+            for (c, counts) in self.deaths.cells.iter().enumerate() {
+                if counts[0] + counts[1] < 0.05 || !visible_cell(&self.deaths.grid, c) { continue; }
+                let (x, y) = self.deaths.grid.corner(c);
+                for (count, color) in counts.iter().zip(["#ff2020", "#ffb000"]) {
+                    if *count <= 0.0 { continue; }
+                    context.set_fill_style_str(color);
+                    context.set_global_alpha((*count as f64 * 0.1).min(0.5));
+                    context.fill_rect(x, y, cell, cell);
+                }
+            }
+            context.set_global_alpha(1.0);
+        }
+        context.set_fill_style_str("rgba(0, 255, 170, 0.08)");
+        context.set_stroke_style_str("rgba(0, 255, 170, 0.5)");
+        context.set_line_width(2.0);
+        context.set_line_dash(&js_sys::Array::of2(&JsValue::from(6.0), &JsValue::from(4.0)))?;
+        for (sx, sy, sr) in self.shelters.iter().filter(|c| visible(c.0, c.1, c.2)) { context.begin_path(); context.arc(*sx, *sy, *sr, 0.0, TAU)?; context.fill(); context.stroke(); }
+        context.set_line_dash(&js_sys::Array::new())?;
+        context.set_fill_style_str(&self.theme.rock); 
+        for (rx, ry, rr) in self.rocks.iter().filter(|c| visible(c.0, c.1, c.2)) { context.begin_path(); context.arc(*rx, *ry, *rr, 0.0, TAU)?; context.fill(); }
+        context.set_stroke_style_str(&self.theme.rock);
+        context.set_line_width(WALL_THICKNESS);
+        context.set_line_cap("round");
+        for b in self.barriers.iter().filter(|b| { let (x, y, r) = b.bounding_circle(); visible(x, y, r) }) {
+            context.begin_path();
+            let points = b.points();
+            context.move_to(points[0].0, points[0].1);
+            for p in &points[1..] { context.line_to(p.0, p.1); }
+            if b.is_wall() { context.stroke(); } else { context.close_path(); context.fill(); }
+        }
+        context.set_line_cap("butt");
 
-        context.set_fill_style(&JsValue::from_str("#1a2b3c")); 
-        for (mx, my, mr) in &self.mud { context.begin_path(); context.arc(*mx, *my, *mr, 0.0, 6.28).unwrap(); context.fill(); }
-        context.set_fill_style(&JsValue::from_str("#555")); 
-        for (rx, ry, rr) in &self.rocks { context.begin_path(); context.arc(*rx, *ry, *rr, 0.0, 6.28).unwrap(); context.fill(); }
+        context.set_fill_style_str("#8b5a2b");
+        for c in self.corpses.iter().filter(|c| visible(c.x, c.y, 4.0)) {
+            context.set_global_alpha((c.energy / CORPSE_ENERGY).clamp(0.2, 1.0));
+            context.begin_path(); context.arc(c.x, c.y, 4.0, 0.0, TAU)?; context.fill();
+        }
+        context.set_global_alpha(1.0);
+        context.set_fill_style_str("#e0e0a0");
+        for (px, py) in self.parasites.iter().filter(|p| visible(p.0, p.1, 2.0)) { context.begin_path(); context.arc(*px, *py, 1.5, 0.0, TAU)?; context.fill(); }
+        context.set_fill_style_str("#b266ff");
+        for s in self.scavengers.iter().filter(|s| visible(s.x, s.y, 4.0)) { context.fill_rect(s.x - 4.0, s.y - 4.0, 8.0, 8.0); }
 
-        context.set_fill_style(&JsValue::from_str("#00ff00"));
-        for (fx, fy) in &self.food { context.begin_path(); context.arc(*fx, *fy, 3.0, 0.0, 6.28).unwrap(); context.fill(); }
+        context.set_fill_style_str(&self.theme.food);
+        for (fx, fy) in self.food.iter().filter(|f| visible(f.0, f.1, FOOD_SPRITE_SIZE)) {
+            match &self.sprites.food {
+                Some(sprite) => sprite.draw(context, *fx, *fy, FOOD_SPRITE_SIZE)?,
+                None => { context.begin_path(); context.arc(*fx, *fy, 3.0, 0.0, TAU)?; context.fill(); }
+            }
+        }
 
-        context.set_fill_style(&JsValue::from_str("#ff0000"));
-        for (px, py) in &self.predators { context.begin_path(); context.move_to(*px, *py - 10.0); context.line_to(*px + 10.0, *py + 10.0); context.line_to(*px - 10.0, *py + 10.0); context.fill(); }
+        if self.predator_territory > 0.0 {
+            context.set_stroke_style_str("rgba(255, 0, 0, 0.15)");
+            context.set_line_width(2.0);
+            for (hx, hy) in self.predator_homes.iter().filter(|h| visible(h.0, h.1, self.predator_territory)) {
+                context.begin_path(); context.arc(*hx, *hy, self.predator_territory, 0.0, TAU)?; context.stroke();
+            }
+        }
+        context.set_fill_style_str(&self.theme.predator);
+        for (i, (px, py)) in self.predators.iter().enumerate() {
+            if !visible(*px, *py, self.predator_vision_range.min(self.width.max(self.height))) { continue; }
+            if self.predator_fov < TAU {
+                let a = self.predator_angles[i];
+                context.set_fill_style_str("rgba(255, 0, 0, 0.04)");
+                context.begin_path(); context.move_to(*px, *py);
+                context.arc(*px, *py, self.predator_vision_range, a - self.predator_fov / 2.0, a + self.predator_fov / 2.0)?;
+                context.close_path(); context.fill();
+                context.set_fill_style_str(&self.theme.predator);
+            }
+            context.set_global_alpha(if self.predator_resting[i] { 0.4 } else { 1.0 });
+            // Pointing the way it last moved
+            context.save();
+            context.translate(*px, *py)?;
+            context.rotate(self.predator_angles[i])?;
+            match &self.sprites.predator {
+                Some(sprite) => sprite.draw(context, 0.0, 0.0, PREDATOR_SPRITE_SIZE)?,
+                None => { context.begin_path(); context.move_to(10.0, 0.0); context.line_to(-10.0, 10.0); context.line_to(-10.0, -10.0); context.fill(); }
+            }
+            context.restore();
+        }
+        context.set_global_alpha(1.0);
+        if self.show_predator_state { self.render_predator_state(context, &visible); }
 
-        for i in 0..self.positions.len() {
-            let (x, y) = self.positions[i];
-            context.set_fill_style(&JsValue::from_str(&self.colors[i]));
-            context.set_global_alpha(self.energies[i] / 100.0);
+        for i in 0..self.agents.positions.len() {
+            let (x, y) = self.agents.positions[i];
+            if self.agents.vacant[i] || !visible(x, y, 30.0) { continue; }
+            if self.burrowed(i) {
+                context.set_fill_style_str("#6b4a2b");
+                context.begin_path(); context.arc(x, y + 2.0, 5.0, PI, TAU)?; context.close_path(); context.fill();
+                continue;
+            }
+            context.set_fill_style_str(&self.palette[self.agents.tribes[i]]);
+            context.set_global_alpha(self.agents.energies[i] / 100.0);
             context.save();
-            context.translate(x, y).unwrap();
-            context.rotate(self.angles[i]).unwrap();
-            context.begin_path(); context.move_to(6.0, 0.0); context.line_to(-4.0, 4.0); context.line_to(-4.0, -4.0); context.fill();
-            if self.energies[i] > WARRIOR_THRESHOLD { context.set_stroke_style(&JsValue::from_str("#ffffff")); context.set_line_width(2.0); context.stroke(); }
+            context.translate(x, y)?;
+            context.rotate(self.agents.angles[i])?;
+            context.scale(self.agents.traits[i].body_size, self.agents.traits[i].body_size)?;
+            match &self.sprites.agent {
+                // Larvae are plain grubs whatever the sprite
+                _ if self.agents.larval[i] => { context.begin_path(); context.ellipse(0.0, 0.0, 5.0, 2.5, 0.0, 0.0, TAU)?; context.fill(); }
+                // Tribe-colored backdrop so tribes stay distinguishable under a shared sprite
+                Some(sprite) => { context.begin_path(); context.arc(0.0, 0.0, 5.0, 0.0, TAU)?; context.fill(); sprite.draw(context, 0.0, 0.0, AGENT_SPRITE_SIZE)?; }
+                None => { context.begin_path(); context.move_to(6.0, 0.0); context.line_to(-4.0, 4.0); context.line_to(-4.0, -4.0); context.fill(); }
+            }
+            if self.agents.energies[i] > WARRIOR_THRESHOLD { context.set_stroke_style_str("#ffffff"); context.set_line_width(2.0); context.stroke(); }
+            // Starving agents blink red
+            if self.agents.energies[i] < STARVING_THRESHOLD && (self.tick / 10).is_multiple_of(2) {
+                context.set_global_alpha(1.0); context.set_stroke_style_str("#ff3333"); context.set_line_width(1.5); context.stroke();
+            }
+            if self.agents.carrying[i].is_some() { context.set_fill_style_str(&self.theme.food); context.begin_path(); context.arc(8.0, 0.0, 2.5, 0.0, TAU)?; context.fill(); }
             context.restore();
-            if self.voices[i] > 0.5 {
-                context.set_stroke_style(&JsValue::from_str("rgba(255, 255, 255, 0.4)"));
+            if self.agents.voices[i] > 0.5 {
+                context.set_stroke_style_str("rgba(255, 255, 255, 0.4)");
                 context.set_line_width(1.0);
-                context.begin_path(); context.arc(x, y, 15.0 + (self.voices[i] * 10.0), 0.0, 6.28).unwrap(); context.stroke();
+                context.begin_path(); context.arc(x, y, 15.0 + (self.agents.voices[i] * 10.0), 0.0, TAU)?; context.stroke();
             }
         }
         context.set_global_alpha(1.0);
+        if self.gene_flow.enabled && self.quality.overlays() { self.render_gene_flow(context); }
+        context.set_stroke_style_str("rgba(255, 255, 255, 0.6)");
+        context.set_line_width(1.0);
+        for i in self.group_members() {
+            let (x, y) = self.agents.positions[i];
+            context.begin_path(); context.arc(x, y, 9.0, 0.0, TAU)?; context.stroke();
+        }
+        if let Some((i, _)) = self.selected.filter(|&s| self.holds(s)) { self.render_selection(context, i)?; }
+        self.render_markers(context, zoom)?;
         context.restore();
+        Ok(())
+    }
+
+    // Sized in screen pixels so labels stay readable at any zoom
+    fn render_markers(&self, context: &web_sys::CanvasRenderingContext2d, zoom: f64) -> Result<(), JsValue> {
+        let (radius, font) = (MARKER_RADIUS / zoom, MARKER_FONT_SIZE / zoom);
+        context.set_font(&format!("{}px sans-serif", font));
+        context.set_line_width(2.0 / zoom);
+        for m in &self.markers {
+            context.set_stroke_style_str(&m.color);
+            context.set_fill_style_str(&m.color);
+            context.begin_path(); context.arc(m.x, m.y, radius, 0.0, TAU)?; context.stroke();
+            context.fill_text(&m.label, m.x + radius * 1.5, m.y - radius)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_free(&self, x: f64, y: f64) -> bool {
+        !self.rock_chunks.at(x, y).iter().any(|&k| { let (rx, ry, rr) = self.rocks[k]; (x - rx).dhypot(y - ry) < rr }) && !self.in_barrier(x, y)
+    }
+
+    // Shared by step_dt and catch_up: up to `count` ticks, stopping early once `budget_ms` of real
+    // time is spent or a lock-step session is waiting on a peer's frame. Every call advances the
+    // world (the callers account for slow motion in time, not by skipping calls); returns the
+    // ticks that ran.
+    fn run_ticks(&mut self, count: u32, budget_ms: f64) -> u32 {
+        let started = clock::now_ms();
+        let first = self.tick;
+        while self.tick - first < count as u64 && self.frame_ready() && clock::now_ms() - started < budget_ms {
+            self.advance();
+        }
+        (self.tick - first) as u32
+    }
+
+    fn render_predator_state(&self, context: &web_sys::CanvasRenderingContext2d, visible: &dyn Fn(f64, f64, f64) -> bool) {
+        context.set_line_width(1.0);
+        for (i, &(px, py)) in self.predators.iter().enumerate() {
+            if !visible(px, py, 20.0) { continue; }
+            if let Some((tx, ty)) = self.predator_hunts[i] {
+                context.set_stroke_style_str("rgba(255, 80, 80, 0.5)");
+                context.begin_path(); context.move_to(px, py); context.line_to(tx, ty); context.stroke();
+            }
+            // Energy (red: hungry) above stamina (blue)
+            let bars = [((self.predator_energies[i] / ENERGY_CAP).clamp(0.0, 1.0), "#ff5555"), ((self.predator_stamina[i] / self.stamina_max).clamp(0.0, 1.0), "#55aaff")];
+            for (k, (fill, color)) in bars.into_iter().enumerate() {
+                let y = py - 18.0 + k as f64 * 4.0;
+                context.set_fill_style_str("rgba(0, 0, 0, 0.5)");
+                context.fill_rect(px - 10.0, y, 20.0, 3.0);
+                context.set_fill_style_str(color);
+                context.fill_rect(px - 10.0, y, 20.0 * fill, 3.0);
+            }
+        }
+    }
+
+    fn snap_to_grid(&mut self) {
+        for (x, y) in self.agents.positions.iter_mut().chain(self.predators.iter_mut()) {
+            *x = fixed::snap(*x, POSITION_SCALE);
+            *y = fixed::snap(*y, POSITION_SCALE);
+        }
+        for (e, vacant) in self.agents.energies.iter_mut().zip(&self.agents.vacant) {
+            let snapped = fixed::snap(*e, ENERGY_SCALE);
+            if !vacant { self.ledger.adjustments += snapped - *e; }
+            *e = snapped;
+        }
+        for e in self.predator_energies.iter_mut() { *e = fixed::snap(*e, ENERGY_SCALE); }
+    }
+
+    // Constant wind plus a cheap sine-based curl that drifts over time
+    fn wind_at(&self, x: f64, y: f64) -> (f64, f64) {
+        if self.wind_turbulence == 0.0 { return self.wind; }
+        let t = self.tick as f64 * WIND_TIME_SCALE;
+        (
+            self.wind.0 + self.wind_turbulence * (y * WIND_SPATIAL_SCALE + t).dsin(),
+            self.wind.1 + self.wind_turbulence * (x * WIND_SPATIAL_SCALE - t * 0.7).dcos(),
+        )
+    }
+
+    // Tip of a whisker ray lands outside the world or inside a rock
+    fn whisker_hit(&self, x: f64, y: f64, angle: f64) -> bool {
+        let rx = x + angle.dcos() * WHISKER_LEN;
+        let ry = y + angle.dsin() * WHISKER_LEN;
+        rx < 0.0 || rx > self.width || ry < 0.0 || ry > self.height || !self.is_free(rx, ry) || self.barrier_blocks((x, y), (rx, ry))
+    }
+
+    // Inspector overlay: selection ring, whiskers (red = touching), hearing range and sensed targets
+    fn render_selection(&self, context: &web_sys::CanvasRenderingContext2d, i: usize) -> Result<(), JsValue> {
+        let (x, y) = self.agents.positions[i];
+        let angle = self.agents.angles[i];
+        context.set_line_width(1.0);
+
+        context.set_stroke_style_str("rgba(255, 255, 255, 0.25)");
+        context.begin_path(); context.arc(x, y, HEARING_RADIUS, 0.0, TAU)?; context.stroke();
+
+        for offset in WHISKER_ANGLES {
+            let a = angle + offset;
+            context.set_stroke_style_str(if self.whisker_hit(x, y, a) { "#ff4444" } else { "#44ff44" });
+            context.begin_path(); context.move_to(x, y); context.line_to(x + a.cos() * WHISKER_LEN, y + a.sin() * WHISKER_LEN); context.stroke();
+        }
+
+        let nearest = |points: &mut dyn Iterator<Item = (f64, f64)>| points.min_by(|a, b| (a.0 - x).hypot(a.1 - y).total_cmp(&(b.0 - x).hypot(b.1 - y)));
+        context.set_line_dash(&js_sys::Array::of2(&JsValue::from(4.0), &JsValue::from(4.0)))?;
+        if let Some((fx, fy)) = nearest(&mut self.food.iter().copied()) {
+            context.set_stroke_style_str("#00ff00");
+            context.begin_path(); context.move_to(x, y); context.line_to(fx, fy); context.stroke();
+        }
+        if let Some((px, py)) = nearest(&mut self.predators.iter().copied()) {
+            context.set_stroke_style_str("#ff0000");
+            context.begin_path(); context.move_to(x, y); context.line_to(px, py); context.stroke();
+        }
+        context.set_line_dash(&js_sys::Array::new())?;
+
+        context.set_stroke_style_str("#ffffff");
+        context.set_line_width(2.0);
+        context.begin_path(); context.arc(x, y, 12.0 * self.agents.traits[i].body_size, 0.0, TAU)?; context.stroke();
+        Ok(())
+    }
+
+    // Slot `i` still holds agent `id`: nobody has since been born or loaded into it
+    fn holds(&self, (i, id): (usize, u64)) -> bool { i < self.agents.len() && !self.agents.vacant[i] && self.agents.ids[i] == id }
+
+    // Born to a mother (not a random newcomer) and still young
+    fn is_juvenile(&self, i: usize) -> bool {
+        self.tick - self.agents.birth_ticks[i] < JUVENILE_TICKS && self.lineage.parent_of(self.agents.ids[i]).is_some()
+    }
+
+    pub(crate) fn burrowed(&self, i: usize) -> bool { self.agents.burrow_until[i] > self.tick }
+
+    // Applies this tick's burrow output; returns whether the agent is underground
+    fn update_burrow(&mut self, i: usize, wants: bool, in_mud: bool) -> bool {
+        let a = &mut self.agents;
+        if self.tick < a.burrow_until[i] {
+            if !wants { a.burrow_until[i] = self.tick; a.burrow_ready[i] = self.tick + BURROW_COOLDOWN; }
+        } else if wants && in_mud && self.tick >= a.burrow_ready[i] {
+            a.burrow_until[i] = self.tick + BURROW_TICKS;
+            a.burrow_ready[i] = a.burrow_until[i] + BURROW_COOLDOWN;
+        }
+        self.burrowed(i)
+    }
+
+    // Moving tires an agent in proportion to its speed; only keeping still lets it recover
+    fn update_fatigue(&mut self, i: usize, speed: f64) {
+        let f = &mut self.agents.fatigue[i];
+        *f = if speed < FATIGUE_REST_SPEED { *f - FATIGUE_RECOVERY } else { *f + speed * FATIGUE_RATE }.clamp(0.0, 1.0);
+    }
+
+    // Larvae turn adult once old or well-fed enough; returns whether the agent is still a larva
+    fn update_stage(&mut self, i: usize) -> bool {
+        if self.agents.larval[i] && (self.tick - self.agents.birth_ticks[i] >= METAMORPHOSIS_AGE || self.agents.energies[i] >= METAMORPHOSIS_ENERGY) {
+            self.agents.larval[i] = false;
+        }
+        self.agents.larval[i]
+    }
+
+    pub(crate) fn in_shelter(&self, x: f64, y: f64) -> bool {
+        self.shelters.iter().any(|(sx, sy, sr)| (x - sx).dhypot(y - sy) < *sr)
+    }
+
+    // Rejection-samples a point satisfying `ok` (falls back to the last try on pathological maps)
+    fn random_position_where(&self, ok: impl Fn(f64, f64) -> bool) -> (f64, f64) {
+        let mut pos = (0.0, 0.0);
+        for _ in 0..SPAWN_ATTEMPTS {
+            pos = (Math::random() * self.width, Math::random() * self.height);
+            if ok(pos.0, pos.1) { break; }
+        }
+        pos
+    }
+
+    // Anywhere outside the rocks
+    pub(crate) fn random_free_position(&self) -> (f64, f64) {
+        self.random_position_where(|x, y| self.is_free(x, y))
+    }
+
+    // Outside rocks and shelters (food and predators)
+    pub(crate) fn random_open_position(&self) -> (f64, f64) {
+        self.random_position_where(|x, y| self.is_free(x, y) && !self.in_shelter(x, y))
+    }
+
+    pub(crate) fn reindex_rocks(&mut self) {
+        self.rock_chunks = ChunkIndex::new(self.width, self.height, CHUNK_SIZE);
+        for (k, &(x, y, r)) in self.rocks.iter().enumerate() { self.rock_chunks.insert_circle(x, y, r, k); }
+    }
+
+    pub(crate) fn reindex_food(&mut self) {
+        self.food_chunks = ChunkIndex::new(self.width, self.height, CHUNK_SIZE);
+        for (k, &(x, y)) in self.food.iter().enumerate() { self.food_chunks.insert_point(x, y, k); }
+    }
+
+    // Predators treat shelters like rocks
+    fn rebuild_nav(&mut self) {
+        let obstacles = [self.rocks.as_slice(), self.shelters.as_slice()].concat();
+        self.nav = FlowField::new(self.width, self.height, NAV_CELL_SIZE, &obstacles);
+        if !self.barriers.is_empty() {
+            self.nav.block_near(|x, y| self.barriers.iter().map(|b| b.distance(x, y)).fold(f64::INFINITY, f64::min));
+        }
+    }
+
+    // Death plus what happens to the slot: freed in dynamic mode, else refilled straight away
+    fn die(&mut self, i: usize) {
+        self.on_death(i);
+        if self.dynamic_population {
+            self.agents.free(i);
+        } else {
+            let parents = self.select_parents(i);
+            self.reproduce(i, parents);
+        }
+    }
+
+    // Death bookkeeping, run right before the slot is handed on. A pregnant mother loses
+    // her unborn offspring and its reserved slot goes to a newcomer instead (or is freed).
+    fn on_death(&mut self, i: usize) {
+        self.possessed = self.possessed.filter(|&(p, _)| p != i);
+        self.selected = self.selected.filter(|&(s, _)| s != i);
+        self.external_controllers.remove(&self.agents.ids[i]);
+        self.record_champion(i);
+        if Math::random() < NOVELTY_ARCHIVE_CHANCE {
+            let desc = self.behavior_descriptor(i);
+            self.novelty_archive.add(desc);
+        }
+        if self.speciation { self.species.remove(self.agents.species_ids[i]); }
+        if self.is_juvenile(i) { self.juvenile_deaths += 1; }
+        // Prey are already down to -10, their reserves booked as predation
+        self.ledger.deaths += self.agents.energies[i].max(0.0) + self.agents.stomachs[i];
+        if let Some(poison) = self.agents.carrying[i].take() { self.place_food(self.agents.positions[i], poison); }
+        self.leave_corpse(self.agents.positions[i].0, self.agents.positions[i].1);
+        self.fertility.add(self.agents.positions[i].0, self.agents.positions[i].1, FERTILITY_DEATH);
+        if let Some(g) = self.agents.gestations[i].take() {
+            if !self.dynamic_population { self.spawn_newcomer(g.slot); } else if let Some(slot) = g.slot { self.agents.free(slot); }
+        }
+    }
+
+    // Refills a dead agent's slot: through a pregnancy if a mate pair was found, else with a newcomer
+    fn reproduce(&mut self, slot: usize, parents: Option<(usize, usize)>) {
+        let Some((p1, p2)) = parents else { self.newcomer_streak += 1; self.spawn_newcomer(Some(slot)); return; };
+        let (mother, father) = match (&self.agents.gestations[p1], &self.agents.gestations[p2]) {
+            (None, _) => (p1, p2),
+            (Some(_), None) => (p2, p1),
+            (Some(_), Some(_)) => { self.spawn_newcomer(Some(slot)); return; }
+        };
+        self.newcomer_streak = 0;
+        self.conceive(Some(slot), mother, father);
+        if self.gestation_ticks == 0 { self.deliver(mother); }
+    }
+
+    // Dynamic mode: a well-fed agent now and then finds a mate, as long as there's room
+    fn try_mate(&mut self, i: usize) {
+        if self.agents.energies[i] <= self.reproduction_threshold || self.agents.breed_ready[i] > self.tick || self.agents.gestations[i].is_some() { return; }
+        if Math::random() >= MATING_CHANCE { return; }
+        let unborn = self.agents.gestations.iter().filter(|g| g.is_some()).count();
+        if self.agents.living() + unborn >= self.carrying_capacity { return; }
+        let Some(mate) = self.pick_parent(&[i], Some(i)) else { return; };
+        if self.agents.energies[mate] <= self.reproduction_threshold { return; }
+        let slot = self.agents.free_slots.pop();
+        self.conceive(slot, i, mate);
+        if self.gestation_ticks == 0 { self.deliver(i); }
+    }
+
+    // Both parents pay up front; the mother carries the genome until it's due
+    fn conceive(&mut self, slot: Option<usize>, mother: usize, father: usize) {
+        let rate = self.tribe_params[self.agents.tribes[mother]].mutation_rate.unwrap_or(self.mutation_rate);
+        let mut brain = self.agents.brains[mother].crossover(&self.agents.brains[father], self.crossover).mutate(rate);
+        if self.sparsity_bias > 0.0 { brain.drop_connections(self.sparsity_bias); }
+        let traits = self.agents.traits[mother].crossover(&self.agents.traits[father], self.crossover).mutate(rate);
+        self.agents.energies[mother] -= self.parent_cost;
+        self.agents.energies[father] -= self.parent_cost;
+        self.ledger.reproduction += 2.0 * self.parent_cost;
+        self.agents.breed_ready[mother] = self.tick + self.reproduction_cooldown;
+        self.agents.breed_ready[father] = self.tick + self.reproduction_cooldown;
+        self.agents.offspring_counts[mother] += 1;
+        self.agents.offspring_counts[father] += 1;
+        if let Some(s) = slot { self.agents.vacant[s] = true; }
+        self.agents.gestations[mother] = Some(Gestation { slot, father: (father, self.agents.ids[father]), due: self.tick + self.gestation_ticks, brain, traits });
+    }
+
+    // The offspring appears next to its mother, in the slot reserved at conception
+    fn deliver(&mut self, mother: usize) {
+        let Some(g) = self.agents.gestations[mother].take() else { return; };
+        let (px, py) = self.agents.positions[mother];
+        let mut position = (px + (Math::random()-0.5)*10.0, py + (Math::random()-0.5)*10.0);
+        if !self.is_free(position.0, position.1) { position = (px, py); }
+        self.note_gene_flow(position, mother, g.father);
+        self.birth(g.slot, Some(mother), g.brain, g.traits, position, self.offspring_energy);
+    }
+
+    fn genome(&self, i: usize) -> Genome {
+        Genome { brain: self.agents.brains[i].clone(), traits: self.agents.traits[i].clone(), tribe: self.agents.tribes[i] }
+    }
+
+    fn living_genomes(&self) -> Vec<Genome> {
+        (0..self.agents.len()).filter(|&i| !self.agents.vacant[i]).map(|i| self.genome(i)).collect()
+    }
+
+    // Replaces every agent with copies of `genomes` (mutated by `mutation`) at random positions.
+    // A fixed-size population cycles through them to fill all slots; a dynamic one gets `count` agents.
+    fn reseed(&mut self, genomes: &[Genome], count: usize, mutation: f64) {
+        for i in 0..self.agents.len() {
+            self.agents.gestations[i] = None;
+            if self.speciation && !self.agents.vacant[i] { self.species.remove(self.agents.species_ids[i]); }
+        }
+        let slots: Vec<Option<usize>> = if self.dynamic_population {
+            self.agents.vacant.fill(true);
+            self.agents.free_slots = (0..self.agents.len()).rev().collect();
+            (0..count.min(self.carrying_capacity)).map(|_| self.agents.free_slots.pop()).collect()
+        } else {
+            (0..self.agents.len()).map(Some).collect()
+        };
+        for (k, slot) in slots.into_iter().enumerate() {
+            let g = &genomes[k % genomes.len()];
+            let position = self.random_free_position();
+            let i = self.birth(slot, None, g.brain.mutate(mutation), g.traits.mutate(mutation), position, STARTING_ENERGY);
+            self.agents.tribes[i] = g.tribe % TRIBE_COUNT;
+            self.place_in_spawn_zone(i);
+        }
+        self.reset_ledger();
+    }
+
+    fn spawn_newcomer(&mut self, slot: Option<usize>) -> usize {
+        let position = self.random_free_position();
+        let i = self.birth(slot, None, Brain::new(), Traits::random(), position, STARTING_ENERGY);
+        self.place_in_spawn_zone(i);
+        i
+    }
+
+    // Offspring inherit the mother's tribe; newcomers get a random one
+    fn birth(&mut self, slot: Option<usize>, mother: Option<usize>, brain: Brain, traits: Traits, position: (f64, f64), energy: f64) -> usize {
+        let (tribe, parent_id) = match mother {
+            Some(m) => (self.agents.tribes[m], Some(self.agents.ids[m])),
+            None => ((Math::random() * TRIBE_COUNT as f64) as usize, None),
+        };
+        let species_id = if self.speciation { self.species.assign(&brain) } else { 0 };
+        let id = self.lineage.record_birth(parent_id, self.tick);
+        if mother.is_some() { self.births += 1; self.note_event(EventKind::Birth, position); }
+        self.ledger.births += energy;
+        self.audio_births += 1;
+        let i = self.agents.spawn(slot, Birth {
+            id, tick: self.tick, position, angle: Math::random() * TAU, energy,
+            brain, traits, tribe, species_id,
+        });
+        self.agents.larval[i] = self.metamorphosis;
+        i
+    }
+
+    fn behavior_descriptor(&self, i: usize) -> Descriptor {
+        let age = (self.tick - self.agents.birth_ticks[i]).max(1) as f64;
+        [
+            self.agents.positions[i].0 / self.width,
+            self.agents.positions[i].1 / self.height,
+            (self.agents.distances[i] / (self.width + self.height)).min(1.0),
+            self.agents.voice_totals[i] / age,
+        ]
+    }
+
+    fn agent_stats(&self, i: usize) -> AgentStats {
+        AgentStats {
+            index: i,
+            id: self.agents.ids[i],
+            x: self.agents.positions[i].0,
+            y: self.agents.positions[i].1,
+            energy: self.agents.energies[i],
+            age: self.tick - self.agents.birth_ticks[i],
+            offspring: self.agents.offspring_counts[i],
+            kills: self.agents.kill_counts[i],
+            thefts: self.agents.theft_counts[i],
+            distance: self.agents.distances[i],
+            tribe: self.agents.tribes[i],
+            color: self.palette[self.agents.tribes[i]].clone(),
+        }
+    }
+
+    fn custom_fitness(&self, callback: &js_sys::Function, i: usize) -> Option<f64> {
+        let stats = serde_wasm_bindgen::to_value(&self.agent_stats(i)).ok()?;
+        callback.call1(&JsValue::NULL, &stats).ok()?.as_f64().filter(|v| v.is_finite())
+    }
+
+    // Custom JS fitness if registered, else behavioral novelty when novelty search is on,
+    // else the weighted fitness objectives. With speciation the score is shared among
+    // the members of the species.
+    fn selection_score(&self, i: usize) -> f64 {
+        let custom = self.fitness_fn.as_ref().and_then(|f| self.custom_fitness(f, i));
+        let score = if let Some(score) = custom {
+            score
+        } else if self.novelty_search {
+            self.novelty_archive.score(&self.behavior_descriptor(i))
+        } else {
+            let age = self.tick - self.agents.birth_ticks[i];
+            self.fitness.score(self.agents.energies[i], self.agents.offspring_counts[i], age, self.agents.kill_counts[i])
+        };
+        if self.speciation { score / self.species.size(self.agents.species_ids[i]) as f64 } else { score }
     }
 }
 
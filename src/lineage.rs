@@ -0,0 +1,110 @@
+use std::collections::{HashMap, HashSet};
+
+pub struct LineageNode {
+    pub parent: Option<u64>,
+    pub born: u64,
+}
+
+// Ancestry of every genome that still has a living descendant.
+// Ids are handed out monotonically, one per birth (or random reset).
+pub struct Lineage {
+    nodes: HashMap<u64, LineageNode>,
+    next_id: u64,
+}
+
+impl Lineage {
+    pub fn new() -> Lineage {
+        Lineage { nodes: HashMap::new(), next_id: 0 }
+    }
+
+    pub fn record_birth(&mut self, parent: Option<u64>, tick: u64) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.insert(id, LineageNode { parent, born: tick });
+        id
+    }
+
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    pub fn parent_of(&self, id: u64) -> Option<u64> {
+        self.nodes.get(&id).and_then(|n| n.parent)
+    }
+
+    // Drops every node that is not an ancestor of (or equal to) a living genome.
+    pub fn prune(&mut self, living: &[u64]) {
+        let mut keep: HashSet<u64> = HashSet::new();
+        for &id in living {
+            let mut cursor = Some(id);
+            while let Some(c) = cursor {
+                if !keep.insert(c) { break; }
+                cursor = self.nodes.get(&c).and_then(|n| n.parent);
+            }
+        }
+        self.nodes.retain(|id, _| keep.contains(id));
+    }
+
+    // Newick tree of the living population, walking at most `max_depth`
+    // generations up from each leaf. Several roots are joined under an unnamed node.
+    pub fn to_newick(&self, living: &[u64], max_depth: u32) -> String {
+        let mut included: HashSet<u64> = HashSet::new();
+        for &id in living {
+            let mut cursor = Some(id);
+            let mut depth = 0;
+            while let Some(c) = cursor {
+                if !self.nodes.contains_key(&c) { break; }
+                included.insert(c);
+                if depth >= max_depth { break; }
+                depth += 1;
+                cursor = self.nodes[&c].parent;
+            }
+        }
+
+        let mut children: HashMap<u64, Vec<u64>> = HashMap::new();
+        let mut roots = Vec::new();
+        for &id in &included {
+            match self.nodes[&id].parent {
+                Some(p) if included.contains(&p) => children.entry(p).or_default().push(id),
+                _ => roots.push(id),
+            }
+        }
+        for list in children.values_mut() { list.sort_unstable(); }
+        roots.sort_unstable();
+
+        let mut out = String::new();
+        if roots.len() == 1 {
+            self.write_node(roots[0], &children, &mut out);
+        } else {
+            out.push('(');
+            for (k, &root) in roots.iter().enumerate() {
+                if k > 0 { out.push(','); }
+                self.write_node(root, &children, &mut out);
+            }
+            out.push(')');
+        }
+        out.push(';');
+        out
+    }
+
+    // Walks the subtree with an explicit stack instead of recursing once per generation, since a
+    // long run's lineage can be deeper than the wasm stack
+    fn write_node(&self, root: u64, children: &HashMap<u64, Vec<u64>>, out: &mut String) {
+        // (node, how many of its children are written so far)
+        let mut stack = vec![(root, 0)];
+        while let Some((id, written)) = stack.pop() {
+            let kids = children.get(&id).map_or(&[][..], |k| k.as_slice());
+            if written < kids.len() {
+                out.push(if written == 0 { '(' } else { ',' });
+                stack.push((id, written + 1));
+                stack.push((kids[written], 0));
+                continue;
+            }
+            if !kids.is_empty() { out.push(')'); }
+            let node = &self.nodes[&id];
+            out.push_str(&format!("g{}", id));
+            // Branch length = ticks between the parent's birth and this one
+            if let Some(parent) = node.parent.and_then(|p| self.nodes.get(&p)) {
+                out.push_str(&format!(":{}", node.born - parent.born));
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::barriers::Barrier;
+use crate::constants::LOCKSTEP_INPUT_DELAY;
+use crate::scenarios::Scenario;
+use crate::Simulation;
+
+// One user intervention, in the shape it travels between peers
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Command {
+    // Any scalar setting by name; see set_param for the list
+    Param { name: String, value: f64 },
+    Wind { dx: f64, dy: f64 },
+    Flocking { separation: f64, alignment: f64, cohesion: f64 },
+    TribeParam { tribe: usize, name: String, value: f64 },
+    ClearTribeParams,
+    BoostTribe { tribe: usize, energy_bonus: f64, ticks: u64 },
+    HandicapTribe { tribe: usize, speed_multiplier: f64, ticks: u64 },
+    ClearInterventions,
+    AddShelter { x: f64, y: f64, radius: f64 },
+    RemoveShelterAt { x: f64, y: f64 },
+    ClearShelters,
+    AddLake { x: f64, y: f64, radius: f64 },
+    ClearLakes,
+    AddWall { x1: f64, y1: f64, x2: f64, y2: f64 },
+    // Flat [x0, y0, x1, y1, ...], as add_polygon
+    AddPolygon { points: Vec<f64> },
+    RemoveBarrierAt { x: f64, y: f64 },
+    ClearBarriers,
+    LoadScenario { scenario: Scenario },
+    ClearScenario,
+    PruneBrains { threshold: f64 },
+    Possess { index: usize },
+    Release,
+    // Same order as set_manual_controls
+    ManualControls { controls: Vec<f64> },
+}
+
+// Everything one peer did that takes effect at `tick`; peers send one per tick, even when empty
+#[derive(Serialize, Deserialize)]
+pub struct InputFrame {
+    pub peer: u32,
+    pub tick: u64,
+    pub commands: Vec<Command>,
+}
+
+pub struct Lockstep {
+    pub peer: u32,
+    // 0 = not in a session: commands apply immediately
+    pub peers: u32,
+    // Ticks between issuing a command and it taking effect, to hide network latency
+    pub delay: u64,
+    pub outgoing: Vec<Command>,
+    pub frames: BTreeMap<u64, Vec<InputFrame>>,
+}
+
+impl Lockstep {
+    pub fn new(peer: u32, peers: u32) -> Lockstep {
+        Lockstep { peer, peers, delay: LOCKSTEP_INPUT_DELAY, outgoing: Vec::new(), frames: BTreeMap::new() }
+    }
+}
+
+impl Simulation {
+    pub(crate) fn enqueue_command(&mut self, command: Command) -> Result<(), String> {
+        if self.lockstep.peers == 0 { return self.execute(&command); }
+        self.lockstep.outgoing.push(command);
+        Ok(())
+    }
+
+    pub(crate) fn take_input_frame(&mut self) -> InputFrame {
+        let frame = InputFrame { peer: self.lockstep.peer, tick: self.tick + self.lockstep.delay, commands: std::mem::take(&mut self.lockstep.outgoing) };
+        self.lockstep.frames.entry(frame.tick).or_default().push(InputFrame { commands: frame.commands.clone(), ..frame });
+        frame
+    }
+
+    pub(crate) fn receive_frame(&mut self, frame: InputFrame) -> Result<(), String> {
+        if frame.tick <= self.tick {
+            return Err(format!("frame from peer {} for tick {} arrived after that tick was simulated (now {})", frame.peer, frame.tick, self.tick));
+        }
+        let frames = self.lockstep.frames.entry(frame.tick).or_default();
+        if frames.iter().any(|f| f.peer == frame.peer) { return Err(format!("duplicate frame from peer {} for tick {}", frame.peer, frame.tick)); }
+        frames.push(frame);
+        Ok(())
+    }
+
+    // Every peer's frame for the coming tick is in (ticks inside the initial delay need none)
+    pub(crate) fn frame_ready(&self) -> bool {
+        let next = self.tick + 1;
+        self.lockstep.peers == 0 || next <= self.lockstep.delay
+            || self.lockstep.frames.get(&next).is_some_and(|f| f.len() as u32 >= self.lockstep.peers)
+    }
+
+    // Runs the frames due this tick, ordered by peer id so every peer applies them identically
+    pub(crate) fn apply_due_frames(&mut self) {
+        let Some(mut frames) = self.lockstep.frames.remove(&self.tick) else { return };
+        frames.sort_by_key(|f| f.peer);
+        for command in frames.iter().flat_map(|f| &f.commands) {
+            if let Err(e) = self.execute(command) { self.log_buffer.push(format!("⚠️ Remote command rejected: {}", e)); }
+        }
+    }
+
+    fn execute(&mut self, command: &Command) -> Result<(), String> {
+        match command {
+            Command::Param { name, value } => return self.set_param(name, *value),
+            Command::Wind { dx, dy } => self.set_wind(*dx, *dy),
+            Command::Flocking { separation, alignment, cohesion } => self.set_flocking(*separation, *alignment, *cohesion),
+            Command::TribeParam { tribe, name, value } => {
+                self.tribe_params.get_mut(*tribe).ok_or(format!("no tribe {}", tribe))?.set(name, *value)?;
+            }
+            Command::ClearTribeParams => self.clear_tribe_params(),
+            Command::BoostTribe { tribe, energy_bonus, ticks } => self.boost_tribe(*tribe, *energy_bonus, *ticks),
+            Command::HandicapTribe { tribe, speed_multiplier, ticks } => self.handicap_tribe(*tribe, *speed_multiplier, *ticks),
+            Command::ClearInterventions => self.clear_interventions(),
+            Command::AddShelter { x, y, radius } => self.add_shelter(*x, *y, *radius),
+            Command::RemoveShelterAt { x, y } => self.remove_shelter_at(*x, *y),
+            Command::ClearShelters => self.clear_shelters(),
+            Command::AddLake { x, y, radius } => self.add_lake(*x, *y, *radius),
+            Command::ClearLakes => self.clear_lakes(),
+            Command::AddWall { x1, y1, x2, y2 } => self.add_wall(*x1, *y1, *x2, *y2),
+            Command::AddPolygon { points } => self.add_barrier(Barrier::polygon_from_flat(points)?),
+            Command::RemoveBarrierAt { x, y } => self.remove_barrier_at(*x, *y),
+            Command::ClearBarriers => self.clear_barriers(),
+            Command::LoadScenario { scenario } => self.load_scenario(*scenario),
+            Command::ClearScenario => self.clear_scenario(),
+            Command::PruneBrains { threshold } => self.prune_brains(*threshold),
+            Command::Possess { index } => self.possess_agent(*index),
+            Command::Release => self.release_agent(),
+            Command::ManualControls { controls } => self.set_manual_controls(controls),
+        }
+        Ok(())
+    }
+
+    // Settings by name: the one table behind the `param` command, pipeline stage params and the
+    // CLI config. Flags read any non-zero value as on.
+    pub fn set_param(&mut self, name: &str, v: f64) -> Result<(), String> {
+        match name {
+            "mutation_rate" => self.set_mutation_rate(v),
+            "predator_speed" => self.set_predator_speed(v),
+            "predator_count" => self.set_predator_count(v as usize),
+            "food_count" => self.set_food_count(v as usize),
+            "scavenger_count" => self.set_scavenger_count(v as usize),
+            "reproduction_threshold" => self.set_reproduction_threshold(v),
+            "reproduction_cooldown" => self.set_reproduction_cooldown(v as u64),
+            "tournament_size" => self.set_tournament_size(v as usize),
+            "gestation_ticks" => self.set_gestation_ticks(v as u64),
+            "starvation_grace" => self.set_starvation_grace(v as u32),
+            "sensor_noise" => self.set_sensor_noise(v),
+            "poison_ratio" => self.set_poison_ratio(v),
+            "eat_radius" => self.set_eat_radius(v),
+            "kill_radius" => self.set_kill_radius(v),
+            "territory_stress" => self.set_territory_stress(v),
+            "wind_turbulence" => self.set_wind_turbulence(v),
+            "sparsity_bias" => self.set_sparsity_bias(v),
+            "food_respawn_delay" => self.set_food_respawn_delay(v as u64),
+            "compatibility_threshold" => self.set_compatibility_threshold(v),
+            "speciation" => self.set_speciation(v != 0.0),
+            "novelty_search" => self.set_novelty_search(v != 0.0),
+            "compass_sensors" => self.set_compass_sensors(v != 0.0),
+            "metamorphosis" => self.set_metamorphosis(v != 0.0),
+            "fatigue" => self.set_fatigue(v != 0.0),
+            "cannibalism" => self.set_cannibalism(v != 0.0),
+            "auto_restart" => self.set_auto_restart(v != 0.0),
+            // 0 turns the dynamic population off
+            "carrying_capacity" => self.set_dynamic_population(v > 0.0, v as usize),
+            _ => return Err(format!("unknown parameter '{}'", name)),
+        }
+        Ok(())
+    }
+}
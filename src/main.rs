@@ -0,0 +1,98 @@
+// Headless evolution runs: `life-sim <config> [out_dir]`
+//
+// The config is plain `key = value` lines (`#` starts a comment). Besides `width`, `height`,
+// `ticks`, `stats_interval`, `seed` and `fixed_point` (both used to build the world),
+// `scenario` (loaded last) and `benchmark` (runs the standard benchmark and prints its score),
+// every key goes through Simulation::set_param (the lock-step `param` table), in file order. Writes
+// `stats.csv` (one row per stats interval) and `champions.bin` (export_champions format).
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use life_simulation::{Scenario, Simulation};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(config_path) = args.get(1) else {
+        eprintln!("usage: life-sim <config> [out_dir]");
+        return ExitCode::FAILURE;
+    };
+    let out_dir = PathBuf::from(args.get(2).map_or(".", |s| s.as_str()));
+    match run(config_path, &out_dir) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => { eprintln!("life-sim: {}", e); ExitCode::FAILURE }
+    }
+}
+
+fn run(config_path: &str, out_dir: &PathBuf) -> Result<(), String> {
+    let text = fs::read_to_string(config_path).map_err(|e| format!("can't read {}: {}", config_path, e))?;
+    let mut config = parse_config(&text)?;
+    let mut take = |key: &str| {
+        let value = config.iter().rev().find(|(k, _)| k == key).map(|(_, v)| *v);
+        config.retain(|(k, _)| k != key);
+        value
+    };
+    let (width, height) = (take("width").unwrap_or(2000.0), take("height").unwrap_or(2000.0));
+    let mut ticks = take("ticks").unwrap_or(100_000.0) as u64;
+    let stats_interval = (take("stats_interval").unwrap_or(100.0) as u64).max(1);
+    // Without a seed the world comes from the platform generator, as in the browser
+    let seed = take("seed");
+    let fixed_point = take("fixed_point").is_some_and(|v| v != 0.0);
+    // 0 = maze, 1 = corridor, 2 = islands, 3 = arena; loaded after every other key
+    let scenario = take("scenario");
+    // Runs the standard benchmark to completion instead of `ticks` ticks
+    let benchmark = take("benchmark").is_some_and(|v| v != 0.0);
+
+    let mut sim = match seed {
+        Some(seed) => Simulation::with_seed(width, height, seed as u64, fixed_point),
+        None => { let mut sim = Simulation::new(width, height); sim.set_fixed_point(fixed_point); sim }
+    };
+    for (key, value) in &config { sim.set_param(key, *value).map_err(|e| format!("config key '{}': {}", key, e))?; }
+    if let Some(v) = scenario {
+        sim.load_scenario(Scenario::from_index(v as u32).ok_or(format!("no scenario {}", v))?);
+    }
+    if benchmark { sim.start_benchmark(); ticks = u64::MAX; }
+
+    fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+    let mut csv = fs::File::create(out_dir.join("stats.csv")).map_err(|e| e.to_string())?;
+    let tribes = sim.get_tribe_stats().len();
+    let header: Vec<String> = (0..tribes).map(|t| format!("tribe_{}", t)).collect();
+    writeln!(csv, "tick,population,avg_energy,species,juvenile_mortality,{}", header.join(",")).map_err(|e| e.to_string())?;
+
+    for _ in 0..ticks {
+        if benchmark && !sim.is_benchmark_running() { break; }
+        sim.step();
+        if !sim.get_tick().is_multiple_of(stats_interval) { continue; }
+        let counts: Vec<String> = sim.get_tribe_stats().iter().map(|c| c.to_string()).collect();
+        writeln!(csv, "{},{},{:.3},{},{:.4},{}", sim.get_tick(), sim.get_population(), sim.get_avg_energy(),
+            sim.get_species_count(), sim.get_juvenile_mortality(), counts.join(",")).map_err(|e| e.to_string())?;
+        let logs = sim.fetch_logs();
+        if !logs.is_empty() { println!("{}", logs); }
+    }
+
+    fs::write(out_dir.join("champions.bin"), sim.export_champions()).map_err(|e| e.to_string())?;
+    println!("ran {} ticks, population {}", sim.get_tick(), sim.get_population());
+    if let Some(score) = sim.get_benchmark_score() {
+        let checkpoints: Vec<String> = sim.get_benchmark_checkpoints().iter().map(|s| s.to_string()).collect();
+        println!("benchmark score {} (checkpoints {})", score, checkpoints.join(", "));
+    }
+    Ok(())
+}
+
+// Keys in file order; a repeated key is applied again, so the last one wins
+fn parse_config(text: &str) -> Result<Vec<(String, f64)>, String> {
+    let mut config = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let (key, value) = line.split_once('=').ok_or(format!("line {}: expected key = value", n + 1))?;
+        let value = match value.trim() {
+            "true" => 1.0,
+            "false" => 0.0,
+            v => v.parse().map_err(|_| format!("line {}: '{}' is not a number", n + 1, v))?,
+        };
+        config.push((key.trim().to_string(), value));
+    }
+    Ok(config)
+}
@@ -0,0 +1,50 @@
+use std::mem::size_of;
+
+use serde::Serialize;
+
+use crate::keyframes::{Keyframe, KeyframeAgent};
+use crate::Simulation;
+
+#[derive(Serialize)]
+pub struct Usage {
+    count: usize,
+    bytes: usize,
+}
+
+// Approximate heap use by category; counts are entities, frames or records depending on the row
+#[derive(Serialize)]
+pub struct MemoryReport {
+    agents: Usage,
+    brains: Usage,
+    food: Usage,
+    energy_history: Usage,
+    stats_history: Usage,
+    keyframes: Usage,
+    lineage: Usage,
+    novelty_archive: Usage,
+    total_bytes: usize,
+}
+
+impl Simulation {
+    pub(crate) fn memory_report(&self) -> MemoryReport {
+        let unborn = self.agents.gestations.iter().flatten().map(|g| &g.brain);
+        let brains: Vec<_> = self.agents.brains.iter().chain(unborn).chain(self.champions.iter().map(|(_, g)| &g.brain)).collect();
+        let keyframe_bytes = self.keyframes.frames.iter()
+            .map(|f| size_of::<Keyframe>() + f.agent_count() * size_of::<KeyframeAgent>() + f.predator_count() * size_of::<(f32, f32)>())
+            .sum();
+        let usage = [
+            (self.agents.len(), self.agents.state_bytes()),
+            (brains.len(), brains.iter().map(|b| b.heap_bytes()).sum()),
+            (self.food.len(), self.food.capacity() * (size_of::<(f64, f64)>() + size_of::<bool>()) + self.pending_food.capacity() * size_of::<u64>()),
+            (self.agents.len(), self.agents.history_bytes()),
+            (self.stats_history.len(), self.stats_history.bytes()),
+            (self.keyframes.frames.len(), keyframe_bytes),
+            // Rough per-node cost of the lineage hash map (id, node, bucket overhead)
+            (self.lineage.len(), self.lineage.len() * 48),
+            (self.novelty_archive.len(), self.novelty_archive.len() * size_of::<crate::novelty::Descriptor>()),
+        ].map(|(count, bytes)| Usage { count, bytes });
+        let total_bytes = usage.iter().map(|u| u.bytes).sum();
+        let [agents, brains, food, energy_history, stats_history, keyframes, lineage, novelty_archive] = usage;
+        MemoryReport { agents, brains, food, energy_history, stats_history, keyframes, lineage, novelty_archive, total_bytes }
+    }
+}
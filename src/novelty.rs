@@ -0,0 +1,38 @@
+// Behavior descriptor: [end x, end y, distance traveled, average voice], all ~0..1
+pub type Descriptor = [f64; 4];
+
+// Bounded ring buffer of past behaviors that novelty is measured against.
+pub struct NoveltyArchive {
+    entries: Vec<Descriptor>,
+    capacity: usize,
+    next: usize,
+    k: usize,
+}
+
+impl NoveltyArchive {
+    pub fn new(capacity: usize, k: usize) -> NoveltyArchive {
+        NoveltyArchive { entries: Vec::new(), capacity, next: 0, k }
+    }
+
+    pub fn add(&mut self, desc: Descriptor) {
+        if self.entries.len() < self.capacity {
+            self.entries.push(desc);
+        } else {
+            self.entries[self.next] = desc;
+            self.next = (self.next + 1) % self.capacity;
+        }
+    }
+
+    // Mean distance to the k nearest archived behaviors (empty archive = maximally novel)
+    pub fn score(&self, desc: &Descriptor) -> f64 {
+        if self.entries.is_empty() { return 1.0; }
+        let mut dists: Vec<f64> = self.entries.iter().map(|e| {
+            e.iter().zip(desc.iter()).map(|(a, b)| (a - b) * (a - b)).sum::<f64>().sqrt()
+        }).collect();
+        dists.sort_by(|a, b| a.total_cmp(b));
+        let k = self.k.min(dists.len());
+        dists[..k].iter().sum::<f64>() / k as f64
+    }
+
+    pub fn len(&self) -> usize { self.entries.len() }
+}
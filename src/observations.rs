@@ -0,0 +1,25 @@
+use crate::constants::{BRAIN_INPUTS, BRAIN_OUTPUTS};
+
+// Observation dump for offline training, little-endian:
+//   magic "LSOB", format version, inputs, outputs, record count (all u32)
+//   then per record: tick (u64), agent id (u64), inputs then outputs (f32 each)
+// Outputs are the ones actually acted on, so controlled agents record their controller's choices.
+const MAGIC: &[u8; 4] = b"LSOB";
+const FORMAT_VERSION: u32 = 1;
+const RECORD_SIZE: usize = 16 + 4 * (BRAIN_INPUTS + BRAIN_OUTPUTS);
+
+pub fn record(buf: &mut Vec<u8>, tick: u64, id: u64, inputs: &[f64; BRAIN_INPUTS], outputs: &[f64; BRAIN_OUTPUTS]) {
+    buf.extend_from_slice(&tick.to_le_bytes());
+    buf.extend_from_slice(&id.to_le_bytes());
+    for v in inputs.iter().chain(outputs) { buf.extend_from_slice(&(*v as f32).to_le_bytes()); }
+}
+
+// Prefixes the header to a buffer of records
+pub fn finish(records: Vec<u8>) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    for v in [FORMAT_VERSION, BRAIN_INPUTS as u32, BRAIN_OUTPUTS as u32, (records.len() / RECORD_SIZE) as u32] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+    out.extend(records);
+    out
+}
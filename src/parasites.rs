@@ -0,0 +1,53 @@
+use crate::rng::Math;
+
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::rng::Stream;
+use crate::Simulation;
+
+impl Simulation {
+    // Free parasites wander; touching an agent makes them latch on, and a new one
+    // appears elsewhere so the pool stays constant
+    pub(crate) fn update_parasites(&mut self) {
+        for p in 0..self.parasites.len() {
+            let (x, y) = self.parasites[p];
+            let heading = Math::random() * std::f64::consts::TAU;
+            let (nx, ny) = ((x + heading.dcos() * PARASITE_DRIFT).clamp(0.0, self.width), (y + heading.dsin() * PARASITE_DRIFT).clamp(0.0, self.height));
+            self.parasites[p] = (nx, ny);
+
+            let host = self.grid.query(nx, ny).into_iter().find(|&j| {
+                let (ax, ay) = self.agents.positions[j];
+                (ax - nx).dhypot(ay - ny) < PARASITE_LATCH_RADIUS && !self.in_water(ax, ay)
+            });
+            if let Some(j) = host {
+                self.agents.parasite_loads[j] = (self.agents.parasite_loads[j] + 1).min(MAX_PARASITE_LOAD);
+                self.parasites[p] = self.random_free_position();
+            }
+        }
+    }
+
+    // An infested agent may pass one parasite on to someone close by
+    pub(crate) fn spread_parasites(&mut self, i: usize, neighbors: &[usize], rng: &mut Stream) {
+        if self.agents.parasite_loads[i] == 0 || rng.next_f64() >= PARASITE_SPREAD_CHANCE { return; }
+        let (x, y) = self.agents.positions[i];
+        let target = neighbors.iter().copied().find(|&j| {
+            j != i && (self.agents.positions[j].0 - x).dhypot(self.agents.positions[j].1 - y) < PARASITE_LATCH_RADIUS
+        });
+        if let Some(j) = target { self.agents.parasite_loads[j] = (self.agents.parasite_loads[j] + 1).min(MAX_PARASITE_LOAD); }
+    }
+
+    // Picks one parasite off the nearest infested tribe-mate; returns whether anyone was groomed
+    pub(crate) fn groom(&mut self, i: usize, neighbors: &[usize]) -> bool {
+        let (x, y) = self.agents.positions[i];
+        let mate = neighbors.iter().copied().find(|&j| {
+            j != i && self.agents.parasite_loads[j] > 0 && self.agents.tribes[j] == self.agents.tribes[i]
+                && (self.agents.positions[j].0 - x).dhypot(self.agents.positions[j].1 - y) < GROOM_RADIUS
+        });
+        if let Some(j) = mate { self.agents.parasite_loads[j] -= 1; }
+        mate.is_some()
+    }
+
+    pub(crate) fn in_water(&self, x: f64, y: f64) -> bool {
+        self.water.iter().any(|(wx, wy, wr)| (x - wx).dhypot(y - wy) < *wr)
+    }
+}
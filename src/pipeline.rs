@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::scenarios::Scenario;
+use crate::Simulation;
+
+// One stage of a staged-training run, as passed to queue_scenario, e.g.
+// { ticks: 20000, scenario: "Maze", params: { predator_count: 4 } }
+#[derive(Deserialize)]
+pub struct Stage {
+    pub ticks: u64,
+    // Layout to load at the start of the stage; none keeps the current terrain
+    #[serde(default)]
+    pub scenario: Option<Scenario>,
+    // Settings by name, as the `param` lockstep command takes them
+    #[serde(default)]
+    pub params: BTreeMap<String, f64>,
+}
+
+// Stages run back to back; whoever survives one (brains, traits, tribes) starts the next
+#[derive(Default)]
+pub struct Pipeline {
+    pub queued: VecDeque<Stage>,
+    // Tick the running stage ends on; None = idle
+    pub stage_end: Option<u64>,
+    // Stages started so far
+    pub stage: u32,
+}
+
+impl Simulation {
+    pub(crate) fn queue_stage(&mut self, stage: Stage) -> Result<(), String> {
+        if stage.ticks == 0 { return Err("a stage needs at least one tick".to_string()); }
+        self.pipeline.queued.push_back(stage);
+        if self.pipeline.stage_end.is_none() { self.next_stage(); }
+        Ok(())
+    }
+
+    fn next_stage(&mut self) {
+        let Some(stage) = self.pipeline.queued.pop_front() else {
+            if self.pipeline.stage_end.take().is_some() { self.log_buffer.push("🎓 Scenario pipeline finished".to_string()); }
+            return;
+        };
+        for (name, value) in &stage.params {
+            if let Err(e) = self.set_param(name, *value) { self.log_buffer.push(format!("⚠️ Stage parameter rejected: {}", e)); }
+        }
+        if let Some(scenario) = stage.scenario { self.load_scenario_inner(scenario); }
+        self.pipeline.stage += 1;
+        self.pipeline.stage_end = Some(self.tick + stage.ticks);
+        self.log_buffer.push(format!("🎓 Stage {} started: {} ticks", self.pipeline.stage, stage.ticks));
+    }
+
+    pub(crate) fn update_pipeline(&mut self) {
+        if self.pipeline.stage_end.is_some_and(|end| self.tick >= end) { self.next_stage(); }
+    }
+}
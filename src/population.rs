@@ -0,0 +1,120 @@
+use crate::brain::Brain;
+use crate::constants::{BRAIN_HIDDEN, BRAIN_INPUTS, BRAIN_OUTPUTS};
+use crate::traits::Traits;
+
+// Binary genome dump, little-endian:
+//   magic "LSPG", format version (u32)
+//   weight encoding (u32, format 3 onwards): 0 = f64, 1 = 8-bit quantized
+//   inputs, hidden, outputs, trait count, genome count (all u32)
+//   run metadata: byte length (u32) then UTF-8 JSON text (format 2 onwards)
+//   then per genome: tribe (u32), traits (f64), then input weights, output weights and biases.
+//   Quantized layers are an offset and scale (f32 each) followed by one u8 per value,
+//   value = offset + q * scale, so each weight is off by at most scale / 2.
+const MAGIC: &[u8; 4] = b"LSPG";
+const FORMAT_VERSION: u32 = 3;
+const ENCODING_F64: u32 = 0;
+const ENCODING_U8: u32 = 1;
+
+#[derive(Clone)]
+pub struct Genome {
+    pub brain: Brain,
+    pub traits: Traits,
+    pub tribe: usize,
+}
+
+pub fn encode(genomes: &[Genome], metadata: &str, quantized: bool) -> Vec<u8> {
+    let trait_count = Traits::random().genes().len();
+    let encoding = if quantized { ENCODING_U8 } else { ENCODING_F64 };
+    let mut out = MAGIC.to_vec();
+    for v in [FORMAT_VERSION, encoding, BRAIN_INPUTS as u32, BRAIN_HIDDEN as u32, BRAIN_OUTPUTS as u32, trait_count as u32, genomes.len() as u32] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+    out.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+    out.extend_from_slice(metadata.as_bytes());
+    for g in genomes {
+        out.extend_from_slice(&(g.tribe as u32).to_le_bytes());
+        for v in g.traits.genes() { out.extend_from_slice(&v.to_le_bytes()); }
+        for layer in [&g.brain.weights_input, &g.brain.weights_output, &g.brain.biases] {
+            if quantized { write_quantized(&mut out, layer); } else { for v in layer { out.extend_from_slice(&v.to_le_bytes()); } }
+        }
+    }
+    out
+}
+
+fn write_quantized(out: &mut Vec<u8>, layer: &[f64]) {
+    let min = layer.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = layer.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let (offset, scale) = if layer.is_empty() { (0.0, 0.0) } else { (min as f32, ((max - min) / 255.0) as f32) };
+    out.extend_from_slice(&offset.to_le_bytes());
+    out.extend_from_slice(&scale.to_le_bytes());
+    for v in layer {
+        let q = if scale > 0.0 { ((v - offset as f64) / scale as f64).round().clamp(0.0, 255.0) } else { 0.0 };
+        out.push(q as u8);
+    }
+}
+
+// Brains saved with another topology are migrated onto the current one. Returns the genomes
+// and the run metadata they were exported with (empty for format 1).
+pub fn decode(bytes: &[u8]) -> Result<(Vec<Genome>, String), String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.take(4)? != MAGIC { return Err("not a population export".to_string()); }
+    let version = reader.u32()?;
+    if version > FORMAT_VERSION { return Err(format!("population format {} is newer than supported format {}", version, FORMAT_VERSION)); }
+    let quantized = match if version >= 3 { reader.u32()? } else { ENCODING_F64 } {
+        ENCODING_F64 => false,
+        ENCODING_U8 => true,
+        e => return Err(format!("unknown weight encoding {}", e)),
+    };
+    let (inputs, hidden, outputs) = (reader.u32()? as usize, reader.u32()? as usize, reader.u32()? as usize);
+    let (trait_count, count) = (reader.u32()? as usize, reader.u32()? as usize);
+    let metadata = if version >= 2 {
+        let len = reader.u32()? as usize;
+        String::from_utf8(reader.take(len)?.to_vec()).map_err(|_| "run metadata is not valid UTF-8")?
+    } else {
+        String::new()
+    };
+
+    let (input_len, output_len, bias_len) = Brain::layer_sizes((inputs, hidden, outputs))?;
+
+    let mut genomes = Vec::new();
+    for _ in 0..count {
+        let tribe = reader.u32()? as usize;
+        let traits = Traits::from_genes(&reader.f64s(trait_count)?);
+        let weights_input = reader.layer(input_len, quantized)?;
+        let weights_output = reader.layer(output_len, quantized)?;
+        let biases = reader.layer(bias_len, quantized)?;
+        let brain = Brain::from_parts((inputs, hidden, outputs), &weights_input, &weights_output, &biases)?;
+        genomes.push(Genome { brain, traits, tribe });
+    }
+    Ok((genomes, metadata))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.bytes.len()).ok_or("population export is truncated")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn layer(&mut self, n: usize, quantized: bool) -> Result<Vec<f64>, String> {
+        if !quantized { return self.f64s(n); }
+        let offset = f32::from_le_bytes(self.take(4)?.try_into().unwrap()) as f64;
+        let scale = f32::from_le_bytes(self.take(4)?.try_into().unwrap()) as f64;
+        Ok(self.take(n)?.iter().map(|&q| offset + q as f64 * scale).collect())
+    }
+
+    fn f64s(&mut self, n: usize) -> Result<Vec<f64>, String> {
+        let raw = self.take(n.checked_mul(8).ok_or("population export is corrupt")?)?;
+        Ok(raw.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap())).collect())
+    }
+}
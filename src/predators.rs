@@ -0,0 +1,142 @@
+use wasm_bindgen::prelude::*;
+use crate::rng::Math;
+use std::f64::consts::{PI, TAU};
+
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::geometry::segment_hits_circle;
+use crate::Simulation;
+
+// How a predator decides which prey to chase
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum PredatorTargeting {
+    Nearest,  // Closest agent or scavenger (classic behavior)
+    Weakest,  // Lowest-energy agent within hunting range
+    Isolated, // Agent farthest from its nearest tribe-mate within hunting range
+    Random,   // A random visible agent, chased until it dies, hides or slips out of view
+}
+
+impl Simulation {
+    // Adds fresh predators at open positions or drops the newest ones
+    pub(crate) fn resize_predators(&mut self, count: usize) {
+        self.predators.truncate(count);
+        self.predator_energies.truncate(count);
+        self.predator_angles.truncate(count);
+        self.predator_stamina.truncate(count);
+        self.predator_resting.truncate(count);
+        self.predator_targets.truncate(count);
+        self.predator_hunts.truncate(count);
+        self.predator_homes.truncate(count);
+        while self.predators.len() < count {
+            let p = self.random_open_position();
+            self.predators.push(p);
+            self.predator_energies.push(PREDATOR_STARTING_ENERGY);
+            self.predator_angles.push(Math::random() * std::f64::consts::TAU);
+            self.predator_stamina.push(self.stamina_max);
+            self.predator_resting.push(false);
+            self.predator_targets.push(None);
+            self.predator_hunts.push(None);
+            self.predator_homes.push(p);
+        }
+    }
+
+    // Living, unsheltered, above-ground agent that a predator may go after
+    fn is_prey(&self, j: usize) -> bool {
+        !self.agents.vacant[j] && !self.burrowed(j) && !self.in_shelter(self.agents.positions[j].0, self.agents.positions[j].1)
+    }
+
+    // Inside the vision cone with no rock in the way, or close enough to be sensed regardless
+    fn can_see(&self, i: usize, x: f64, y: f64) -> bool {
+        let (px, py) = self.predators[i];
+        let dist = (x - px).dhypot(y - py);
+        if dist < PREDATOR_SENSE_RADIUS { return true; }
+        if dist > self.predator_vision_range { return false; }
+        let off = ((y - py).datan2(x - px) - self.predator_angles[i] + PI).rem_euclid(TAU) - PI;
+        off.abs() <= self.predator_fov / 2.0
+            && !self.rocks.iter().any(|(rx, ry, rr)| segment_hits_circle(px, py, x, y, *rx, *ry, *rr))
+            && !self.barrier_blocks((px, py), (x, y))
+    }
+
+    // Territorial predators only go after what's inside their own patch
+    fn in_territory(&self, i: usize, x: f64, y: f64) -> bool {
+        let (hx, hy) = self.predator_homes[i];
+        self.predator_territory == 0.0 || (x - hx).dhypot(y - hy) <= self.predator_territory
+    }
+
+    fn can_hunt(&self, i: usize, j: usize) -> bool {
+        self.is_prey(j) && !self.tolerated(j, i) && self.can_see_agent(i, j) && self.in_territory(i, self.agents.positions[j].0, self.agents.positions[j].1)
+    }
+
+    // Torpid agents lie low: only noticed when a predator is practically on top of them
+    fn can_see_agent(&self, i: usize, j: usize) -> bool {
+        let (ax, ay) = self.agents.positions[j];
+        if self.agents.torpid[j] {
+            let (px, py) = self.predators[i];
+            return (ax - px).dhypot(ay - py) < PREDATOR_SENSE_RADIUS;
+        }
+        self.can_see(i, ax, ay)
+    }
+
+    // Where predator `i` is heading this tick, or None if it can't see anything to hunt
+    pub(crate) fn predator_target(&mut self, i: usize) -> Option<(f64, f64)> {
+        let (px, py) = self.predators[i];
+        let in_range = |s: &Simulation, j: usize| {
+            let (ax, ay) = s.agents.positions[j];
+            (ax - px).dhypot(ay - py) < PREDATOR_HUNT_RADIUS && s.can_hunt(i, j)
+        };
+        let best = match self.predator_targeting {
+            PredatorTargeting::Nearest => None,
+            PredatorTargeting::Weakest => (0..self.agents.len())
+                .filter(|&j| in_range(self, j))
+                .min_by(|&a, &b| self.agents.energies[a].total_cmp(&self.agents.energies[b])),
+            PredatorTargeting::Isolated => (0..self.agents.len())
+                .filter(|&j| in_range(self, j))
+                .map(|j| (j, self.nearest_tribe_mate_dist(j)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(j, _)| j),
+            PredatorTargeting::Random => self.random_prey(i),
+        };
+        match best {
+            Some(j) => Some(self.agents.positions[j]),
+            None => self.nearest_prey(i),
+        }
+    }
+
+    // With nothing in sight, keep prowling roughly straight ahead (turning back near the edge of
+    // its territory, if it has one)
+    pub(crate) fn predator_wander_point(&mut self, i: usize) -> (f64, f64) {
+        self.predator_angles[i] += (Math::random() - 0.5) * 0.3;
+        let (px, py) = self.predators[i];
+        let (hx, hy) = self.predator_homes[i];
+        if self.predator_territory > 0.0 && (px - hx).dhypot(py - hy) > self.predator_territory * PATROL_TURNBACK { return (hx, hy); }
+        (px + self.predator_angles[i].dcos() * 50.0, py + self.predator_angles[i].dsin() * 50.0)
+    }
+
+    fn nearest_prey(&self, i: usize) -> Option<(f64, f64)> {
+        let (px, py) = self.predators[i];
+        let agents = (0..self.agents.len()).filter(|&j| self.can_hunt(i, j)).map(|j| self.agents.positions[j]);
+        let scavengers = self.scavengers.iter().map(|s| (s.x, s.y)).filter(|&(x, y)| self.can_see(i, x, y) && self.in_territory(i, x, y));
+        agents.chain(scavengers).min_by(|a, b| (a.0 - px).dhypot(a.1 - py).total_cmp(&(b.0 - px).dhypot(b.1 - py)))
+    }
+
+    // Distance to the closest same-tribe agent in the surrounding grid cells
+    fn nearest_tribe_mate_dist(&self, j: usize) -> f64 {
+        let (x, y) = self.agents.positions[j];
+        self.grid.query(x, y).into_iter()
+            .filter(|&k| k != j && self.agents.tribes[k] == self.agents.tribes[j])
+            .map(|k| (self.agents.positions[k].0 - x).dhypot(self.agents.positions[k].1 - y))
+            .fold(PREDATOR_HUNT_RADIUS, f64::min)
+    }
+
+    // Sticks with the remembered target (by stable id) and only re-rolls once it's gone
+    fn random_prey(&mut self, i: usize) -> Option<usize> {
+        let current = self.predator_targets[i].and_then(|id| self.agents.ids.iter().position(|&x| x == id));
+        let visible = |s: &Simulation, j: usize| s.can_hunt(i, j);
+        if let Some(j) = current.filter(|&j| visible(self, j)) { return Some(j); }
+        let total = self.agents.len();
+        let pick = (0..SPAWN_ATTEMPTS).map(|_| (Math::random() * total as f64) as usize).find(|&j| visible(self, j));
+        self.predator_targets[i] = pick.map(|j| self.agents.ids[j]);
+        pick
+    }
+}
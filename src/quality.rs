@@ -0,0 +1,47 @@
+use crate::constants::{QUALITY_ADJUST_TICKS, QUALITY_MAX, QUALITY_SMOOTHING};
+use crate::Simulation;
+
+// Step-time budget controller. Levels, from QUALITY_MAX down:
+//   3 everything on, 2 no terrain overlays (fertility, trails, territory),
+//   1 also no voice propagation, 0 also a capped sensor range
+pub struct Quality {
+    // 0 = no budget: always full quality
+    pub target_ms: f64,
+    pub level: u32,
+    // Exponential moving average of step duration
+    pub avg_ms: f64,
+    pub last_change: u64,
+}
+
+impl Default for Quality {
+    fn default() -> Quality {
+        Quality { target_ms: 0.0, level: QUALITY_MAX, avg_ms: 0.0, last_change: 0 }
+    }
+}
+
+impl Quality {
+    pub fn overlays(&self) -> bool { self.level >= 3 }
+    pub fn voices(&self) -> bool { self.level >= 2 }
+    pub fn full_sensor_range(&self) -> bool { self.level >= 1 }
+}
+
+impl Simulation {
+    // Steps down a level while over budget and back up once comfortably under it,
+    // at most once every QUALITY_ADJUST_TICKS ticks
+    pub(crate) fn adapt_quality(&mut self, step_ms: f64) {
+        let q = &mut self.quality;
+        q.avg_ms += (step_ms - q.avg_ms) * QUALITY_SMOOTHING;
+        if q.target_ms <= 0.0 { q.level = QUALITY_MAX; return; }
+        if self.tick - q.last_change < QUALITY_ADJUST_TICKS { return; }
+        let level = if q.avg_ms > q.target_ms && q.level > 0 {
+            q.level - 1
+        } else if q.avg_ms < q.target_ms * 0.6 && q.level < QUALITY_MAX {
+            q.level + 1
+        } else {
+            return;
+        };
+        q.level = level;
+        q.last_change = self.tick;
+        self.log_buffer.push(format!("⚙️ Quality level {} (step {:.1} ms)", level, self.quality.avg_ms));
+    }
+}
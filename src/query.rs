@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::fitness::AgentStats;
+use crate::Simulation;
+
+// Whatever sits under the cursor, tagged with `kind` for the frontend
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EntityInfo {
+    Agent(AgentStats),
+    Predator { index: usize, x: f64, y: f64, energy: f64, stamina: f64, resting: bool },
+    Scavenger { index: usize, x: f64, y: f64, energy: f64 },
+    Food { index: usize, x: f64, y: f64, poison: bool },
+    Corpse { index: usize, x: f64, y: f64, energy: f64 },
+    Rock { index: usize, x: f64, y: f64, radius: f64 },
+    Shelter { index: usize, x: f64, y: f64, radius: f64 },
+    Lake { index: usize, x: f64, y: f64, radius: f64 },
+    Mud { index: usize, x: f64, y: f64, radius: f64 },
+    Barrier { index: usize, points: Vec<(f64, f64)> },
+}
+
+impl Simulation {
+    // Nearest point-like entity within `pick_radius`, else the terrain feature containing the point
+    pub(crate) fn entity_at(&self, x: f64, y: f64, pick_radius: f64) -> Option<EntityInfo> {
+        let dist = |ex: f64, ey: f64| (ex - x).hypot(ey - y);
+        let mut best: Option<(f64, EntityInfo)> = None;
+        let mut consider = |d: f64, make: &dyn Fn() -> EntityInfo| {
+            if d < pick_radius && best.as_ref().is_none_or(|(bd, _)| d < *bd) { best = Some((d, make())); }
+        };
+
+        for i in 0..self.agents.len() {
+            if self.agents.vacant[i] { continue; }
+            let (ax, ay) = self.agents.positions[i];
+            consider(dist(ax, ay), &|| EntityInfo::Agent(self.agent_stats(i)));
+        }
+        for (i, &(px, py)) in self.predators.iter().enumerate() {
+            consider(dist(px, py), &|| EntityInfo::Predator {
+                index: i, x: px, y: py, energy: self.predator_energies[i],
+                stamina: self.predator_stamina[i], resting: self.predator_resting[i],
+            });
+        }
+        for (i, s) in self.scavengers.iter().enumerate() {
+            consider(dist(s.x, s.y), &|| EntityInfo::Scavenger { index: i, x: s.x, y: s.y, energy: s.energy });
+        }
+        for (i, &(fx, fy)) in self.food.iter().enumerate() {
+            consider(dist(fx, fy), &|| EntityInfo::Food { index: i, x: fx, y: fy, poison: self.food_poison[i] });
+        }
+        for (i, c) in self.corpses.iter().enumerate() {
+            consider(dist(c.x, c.y), &|| EntityInfo::Corpse { index: i, x: c.x, y: c.y, energy: c.energy });
+        }
+        if let Some((_, info)) = best { return Some(info); }
+
+        // Terrain, topmost layer first (same order as drawn, reversed)
+        if let Some(i) = self.barriers.iter().position(|b| b.contains(x, y)) { return Some(EntityInfo::Barrier { index: i, points: self.barriers[i].points().to_vec() }); }
+        let inside = |list: &[(f64, f64, f64)]| list.iter().position(|&(cx, cy, r)| dist(cx, cy) < r);
+        if let Some(i) = inside(&self.rocks) { let (x, y, radius) = self.rocks[i]; return Some(EntityInfo::Rock { index: i, x, y, radius }); }
+        if let Some(i) = inside(&self.shelters) { let (x, y, radius) = self.shelters[i]; return Some(EntityInfo::Shelter { index: i, x, y, radius }); }
+        if let Some(i) = inside(&self.water) { let (x, y, radius) = self.water[i]; return Some(EntityInfo::Lake { index: i, x, y, radius }); }
+        if let Some(i) = inside(&self.mud) { let (x, y, radius) = self.mud[i]; return Some(EntityInfo::Mud { index: i, x, y, radius }); }
+        None
+    }
+}
@@ -0,0 +1,78 @@
+use wasm_bindgen::prelude::*;
+
+use crate::constants::*;
+use crate::death_map::DeathMap;
+use crate::fertility::FertilityMap;
+use crate::geometry::random_circle;
+use crate::spatial_grid::SpatialGrid;
+use crate::territory::TerritoryMap;
+use crate::trails::TrailMap;
+use crate::Simulation;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResizeMode {
+    RescaleEntities, // Stretch every position with the world, keeping the layout
+    ClampEntities,   // Keep positions; anything now outside is pushed back to the nearest edge
+    ExpandOnly,      // Keep positions; shrinking either side is refused
+}
+
+impl Simulation {
+    // Changes the world size, moving entities per `mode`, and keeps terrain features and food at
+    // the same count per unit area by adding random ones or dropping the excess
+    pub(crate) fn resize_world_inner(&mut self, width: f64, height: f64, mode: ResizeMode) -> Result<(), String> {
+        if !(width > 0.0 && height > 0.0) { return Err(format!("world size must be positive, got {}x{}", width, height)); }
+        if mode == ResizeMode::ExpandOnly && (width < self.width || height < self.height) {
+            return Err(format!("can't shrink {}x{} to {}x{} in ExpandOnly mode", self.width, self.height, width, height));
+        }
+        let (sx, sy) = (width / self.width, height / self.height);
+        let remap = |p: &mut (f64, f64)| match mode {
+            ResizeMode::RescaleEntities => { p.0 *= sx; p.1 *= sy; }
+            ResizeMode::ClampEntities | ResizeMode::ExpandOnly => { p.0 = p.0.clamp(0.0, width); p.1 = p.1.clamp(0.0, height); }
+        };
+
+        let a = &mut self.agents;
+        a.positions.iter_mut().chain(a.spawn_points.iter_mut()).chain(a.last_meals.iter_mut().flatten()).for_each(remap);
+        self.predators.iter_mut().chain(self.predator_homes.iter_mut()).chain(self.food.iter_mut()).chain(self.parasites.iter_mut()).for_each(remap);
+        self.last_events.iter_mut().flatten().for_each(remap);
+        let scale_zone = |z: &mut (f64, f64, f64)| { let mut p = (z.0, z.1); remap(&mut p); (z.0, z.1) = p; };
+        self.food_zones.iter_mut().chain(self.spawn_zones.iter_mut()).for_each(scale_zone);
+        self.barriers = self.barriers.iter().map(|b| b.map_points(remap)).collect();
+        for (x, y) in self.corpses.iter_mut().map(|c| (&mut c.x, &mut c.y))
+            .chain(self.scavengers.iter_mut().map(|s| (&mut s.x, &mut s.y)))
+            .chain(self.hotspots.iter_mut().map(|h| (&mut h.x, &mut h.y)))
+            .chain(self.markers.iter_mut().map(|m| (&mut m.x, &mut m.y)))
+        {
+            let mut p = (*x, *y);
+            remap(&mut p);
+            (*x, *y) = p;
+        }
+
+        // Terrain moves with the world when rescaling; otherwise features left outside are dropped
+        let area_ratio = (width * height) / (self.width * self.height);
+        for (features, radius) in [(&mut self.rocks, ROCK_RADIUS), (&mut self.mud, MUD_RADIUS), (&mut self.water, LAKE_RADIUS), (&mut self.shelters, SHELTER_RADIUS)] {
+            let target = (features.len() as f64 * area_ratio).round() as usize;
+            match mode {
+                ResizeMode::RescaleEntities => features.iter_mut().for_each(|f| { f.0 *= sx; f.1 *= sy; }),
+                _ => features.retain(|f| f.0 <= width && f.1 <= height),
+            }
+            features.truncate(target);
+            while features.len() < target { features.push(random_circle(width, height, radius)); }
+        }
+
+        let food_target = (self.food.len() as f64 * area_ratio).round() as usize;
+        self.width = width;
+        self.height = height;
+        self.grid = SpatialGrid::new(width, height, 100.0);
+        self.territory = TerritoryMap::new(width, height, TERRITORY_CELL_SIZE);
+        self.fertility = FertilityMap::new(width, height, FERTILITY_CELL_SIZE);
+        self.trails = TrailMap::new(width, height, TRAIL_CELL_SIZE);
+        self.deaths = DeathMap::new(width, height, DEATH_MAP_CELL_SIZE);
+        self.rebuild_nav();
+        self.reindex_rocks();
+        self.reindex_barriers();
+        self.reindex_food();
+        self.set_food_count(food_target);
+        Ok(())
+    }
+}
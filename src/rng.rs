@@ -0,0 +1,89 @@
+use std::cell::Cell;
+
+use crate::fixed::DetMath;
+
+// Every shared (non-agent) draw goes through here. Normally that's the platform generator;
+// reseed() swaps in a seeded xorshift64* so a fixed-point run replays exactly.
+pub struct Math;
+
+thread_local! {
+    static SEEDED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+impl Math {
+    pub fn random() -> f64 {
+        SEEDED.with(|s| match s.get() {
+            Some(state) => { let (x, v) = xorshift(state); s.set(Some(x)); v }
+            None => platform_random(),
+        })
+    }
+}
+
+// None hands the shared draws back to the platform generator
+pub fn reseed(seed: Option<u64>) {
+    restore(seed.map(|x| splitmix64(x) | 1));
+}
+
+// Where the seeded generator is (None = platform), for restore() to pick up from later
+pub fn state() -> Option<u64> { SEEDED.with(|s| s.get()) }
+
+pub fn restore(state: Option<u64>) { SEEDED.with(|s| s.set(state)); }
+
+#[cfg(target_arch = "wasm32")]
+fn platform_random() -> f64 { js_sys::Math::random() }
+
+// Native stand-in for js_sys::Math (which panics off wasm), so the headless CLI can run the
+// same code: xorshift64* seeded from the clock
+#[cfg(not(target_arch = "wasm32"))]
+fn platform_random() -> f64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(1, |d| d.as_nanos() as u64) | 1
+        );
+    }
+    STATE.with(|s| { let (x, v) = xorshift(s.get()); s.set(x); v })
+}
+
+fn xorshift(mut x: u64) -> (u64, f64) {
+    x ^= x >> 12; x ^= x << 25; x ^= x >> 27;
+    (x, (x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64)
+}
+
+// Standard normal sample (Box-Muller)
+pub fn gaussian() -> f64 {
+    let u1 = Math::random().max(f64::MIN_POSITIVE);
+    let u2 = Math::random();
+    (-2.0 * u1.dln()).sqrt() * (std::f64::consts::TAU * u2).dcos()
+}
+
+// Counter-based stream keyed on (seed, agent id, tick): the n-th draw is a pure hash of the key
+// and n, so an agent's randomness doesn't depend on the order agents are processed in
+pub struct Stream {
+    key: u64,
+    counter: u64,
+}
+
+impl Stream {
+    pub fn new(seed: u64, id: u64, tick: u64) -> Stream {
+        Stream { key: splitmix64(seed ^ splitmix64(id ^ splitmix64(tick))), counter: 0 }
+    }
+
+    // Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        self.counter += 1;
+        (splitmix64(self.key.wrapping_add(self.counter)) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn gaussian(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.dln()).sqrt() * (std::f64::consts::TAU * u2).dcos()
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
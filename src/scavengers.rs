@@ -0,0 +1,87 @@
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::Simulation;
+
+// What's left of a dead agent; its energy rots away over time
+pub struct Corpse {
+    pub x: f64,
+    pub y: f64,
+    pub energy: f64,
+}
+
+// Scripted carrion eaters: they run from agents, home in on corpses and are prey for predators
+pub struct Scavenger {
+    pub x: f64,
+    pub y: f64,
+    pub energy: f64,
+}
+
+impl Simulation {
+    pub(crate) fn leave_corpse(&mut self, x: f64, y: f64) {
+        if self.corpses.len() >= MAX_CORPSES { self.ledger.decay += self.corpses.remove(0).energy; }
+        self.corpses.push(Corpse { x, y, energy: CORPSE_ENERGY });
+        self.ledger.corpses += CORPSE_ENERGY;
+    }
+
+    pub(crate) fn decay_corpses(&mut self) {
+        for c in &mut self.corpses {
+            self.ledger.decay += c.energy.min(CORPSE_DECAY);
+            c.energy -= CORPSE_DECAY;
+        }
+        self.corpses.retain(|c| c.energy > 0.0);
+    }
+
+    pub(crate) fn nearest_corpse(&self, x: f64, y: f64) -> Option<(usize, f64)> {
+        self.corpses.iter().enumerate()
+            .map(|(idx, c)| (idx, (c.x - x).dhypot(c.y - y)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    // Takes up to `amount` out of a corpse, removing it once it's picked clean
+    pub(crate) fn eat_corpse(&mut self, idx: usize, amount: f64) -> f64 {
+        let taken = self.corpses[idx].energy.min(amount);
+        self.corpses[idx].energy -= taken;
+        if self.corpses[idx].energy <= 0.0 { self.corpses.swap_remove(idx); }
+        taken
+    }
+
+    pub(crate) fn spawn_scavenger(&self) -> Scavenger {
+        let (x, y) = self.random_free_position();
+        Scavenger { x, y, energy: SCAVENGER_STARTING_ENERGY }
+    }
+
+    pub(crate) fn update_scavengers(&mut self) {
+        for s in 0..self.scavengers.len() {
+            let (x, y) = (self.scavengers[s].x, self.scavengers[s].y);
+
+            // Fleeing beats feeding
+            let mut threat: Option<(f64, f64, f64)> = None;
+            for j in self.grid.query(x, y) {
+                let (ax, ay) = self.agents.positions[j];
+                let d = (ax - x).dhypot(ay - y);
+                if d < SCAVENGER_FLEE_RADIUS && threat.is_none_or(|t| d < t.2) { threat = Some((ax, ay, d)); }
+            }
+            let (dx, dy) = match (threat, self.nearest_corpse(x, y)) {
+                (Some((ax, ay, d)), _) => ((x - ax) / d.max(0.001), (y - ay) / d.max(0.001)),
+                (None, Some((idx, d))) if d > 0.0 => ((self.corpses[idx].x - x) / d, (self.corpses[idx].y - y) / d),
+                _ => (0.0, 0.0),
+            };
+
+            let (nx, ny) = (x + dx * SCAVENGER_SPEED, y + dy * SCAVENGER_SPEED);
+            if self.is_free(nx, ny) {
+                self.scavengers[s].x = nx.clamp(0.0, self.width);
+                self.scavengers[s].y = ny.clamp(0.0, self.height);
+            }
+            self.scavengers[s].energy -= SCAVENGER_UPKEEP;
+
+            if let Some((idx, d)) = self.nearest_corpse(self.scavengers[s].x, self.scavengers[s].y) {
+                if d < self.eat_radius {
+                    let gained = self.eat_corpse(idx, FOOD_ENERGY);
+                    self.ledger.scavenged += gained;
+                    self.scavengers[s].energy = (self.scavengers[s].energy + gained).min(ENERGY_CAP);
+                }
+            }
+            if self.scavengers[s].energy <= 0.0 { self.scavengers[s] = self.spawn_scavenger(); }
+        }
+    }
+}
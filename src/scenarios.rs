@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use crate::rng::Math;
+
+use crate::barriers::Barrier;
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::Simulation;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Scenario {
+    Maze,     // Random perfect maze: spawn in the top-left cell, food in the bottom-right one
+    Corridor, // One long walled corridor with staggered baffles: spawn at the left end, food at the right
+    Islands,  // Food only on walled islands, each with a single opening
+    Arena,    // Walled square; each tribe starts in its own corner, food in the middle
+}
+
+impl Scenario {
+    // Config files number them in declaration order
+    pub fn from_index(index: u32) -> Option<Scenario> {
+        [Scenario::Maze, Scenario::Corridor, Scenario::Islands, Scenario::Arena].get(index as usize).copied()
+    }
+}
+
+impl Simulation {
+    // Replaces the terrain with the scenario's layout, then moves every agent to a spawn zone and
+    // regrows all food inside the food zones. Each food zone gets a small lake so nobody dies of
+    // thirst.
+    pub(crate) fn load_scenario_inner(&mut self, scenario: Scenario) {
+        self.rocks.clear();
+        self.mud.clear();
+        self.shelters.clear();
+        self.water.clear();
+        self.barriers.clear();
+        let (w, h) = (self.width, self.height);
+        let (walls, food_zones, spawn_zones) = match scenario {
+            Scenario::Maze => maze(w, h),
+            Scenario::Corridor => corridor(w, h),
+            Scenario::Islands => islands(w, h),
+            Scenario::Arena => arena(w, h),
+        };
+        self.barriers = walls.into_iter().map(|(a, b)| Barrier::wall(a, b)).collect();
+        self.water = food_zones.iter().map(|&(x, y, _)| (x, y, SCENARIO_LAKE_RADIUS)).collect();
+        self.food_zones = food_zones;
+        self.spawn_zones = spawn_zones;
+        self.reindex_rocks();
+        self.reindex_barriers();
+        self.rebuild_nav();
+
+        for i in 0..self.agents.len() {
+            if !self.agents.vacant[i] { self.place_in_spawn_zone(i); }
+        }
+        for p in 0..self.predators.len() {
+            self.predators[p] = self.random_open_position();
+            self.predator_homes[p] = self.predators[p];
+        }
+        let count = self.food.len();
+        self.truncate_food(0);
+        self.set_food_count(count);
+    }
+
+    // Newcomers start in their tribe's spawn zone when a scenario defines them
+    pub(crate) fn place_in_spawn_zone(&mut self, i: usize) {
+        if self.spawn_zones.is_empty() { return; }
+        let zone = self.spawn_zones[self.agents.tribes[i] % self.spawn_zones.len()];
+        if let Some(p) = self.free_position_in(zone) {
+            self.agents.positions[i] = p;
+            self.agents.spawn_points[i] = p;
+        }
+    }
+
+    pub(crate) fn free_position_in(&self, (cx, cy, r): (f64, f64, f64)) -> Option<(f64, f64)> {
+        (0..SPAWN_ATTEMPTS).map(|_| {
+            let (angle, dist) = (Math::random() * std::f64::consts::TAU, Math::random().sqrt() * r);
+            (cx + angle.dcos() * dist, cy + angle.dsin() * dist)
+        }).find(|&(x, y)| x >= 0.0 && x <= self.width && y >= 0.0 && y <= self.height && self.is_free(x, y))
+    }
+}
+
+type Wall = ((f64, f64), (f64, f64));
+type Layout = (Vec<Wall>, Vec<(f64, f64, f64)>, Vec<(f64, f64, f64)>);
+
+// Depth-first carving over a grid of roughly MAZE_CELL_SIZE cells; every wall left standing
+// becomes a segment
+fn maze(w: f64, h: f64) -> Layout {
+    let (cols, rows) = (((w / MAZE_CELL_SIZE) as usize).max(2), ((h / MAZE_CELL_SIZE) as usize).max(2));
+    let (cw, ch) = (w / cols as f64, h / rows as f64);
+    // open[cell] = [right, down]
+    let mut open = vec![[false; 2]; cols * rows];
+    let mut visited = vec![false; cols * rows];
+    let mut stack = vec![0];
+    visited[0] = true;
+    while let Some(&cell) = stack.last() {
+        let (c, r) = (cell % cols, cell / cols);
+        let mut next = Vec::new();
+        if c > 0 { next.push(cell - 1); }
+        if c + 1 < cols { next.push(cell + 1); }
+        if r > 0 { next.push(cell - cols); }
+        if r + 1 < rows { next.push(cell + cols); }
+        next.retain(|&n| !visited[n]);
+        if next.is_empty() { stack.pop(); continue; }
+        let n = next[(Math::random() * next.len() as f64) as usize];
+        let (lo, hi) = (cell.min(n), cell.max(n));
+        open[lo][if hi == lo + 1 { 0 } else { 1 }] = true;
+        visited[n] = true;
+        stack.push(n);
+    }
+
+    let mut walls = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            let (x0, y0, x1, y1) = (c as f64 * cw, r as f64 * ch, (c + 1) as f64 * cw, (r + 1) as f64 * ch);
+            if c + 1 < cols && !open[r * cols + c][0] { walls.push(((x1, y0), (x1, y1))); }
+            if r + 1 < rows && !open[r * cols + c][1] { walls.push(((x0, y1), (x1, y1))); }
+        }
+    }
+    let zone = cw.min(ch) * 0.35;
+    (walls, vec![(w - cw / 2.0, h - ch / 2.0, zone)], vec![(cw / 2.0, ch / 2.0, zone)])
+}
+
+fn corridor(w: f64, h: f64) -> Layout {
+    let (top, bottom) = (h / 2.0 - CORRIDOR_WIDTH / 2.0, h / 2.0 + CORRIDOR_WIDTH / 2.0);
+    let mut walls = vec![((0.0, top), (w, top)), ((0.0, bottom), (w, bottom))];
+    // Baffles alternate from the top and bottom walls, each leaving a gap on the other side
+    let baffles = (w / (CORRIDOR_WIDTH * 3.0)) as usize;
+    for k in 1..baffles {
+        let x = w * k as f64 / baffles as f64;
+        let reach = CORRIDOR_WIDTH * 0.6;
+        walls.push(if k % 2 == 0 { ((x, top), (x, top + reach)) } else { ((x, bottom), (x, bottom - reach)) });
+    }
+    let zone = CORRIDOR_WIDTH * 0.4;
+    (walls, vec![(w - CORRIDOR_WIDTH / 2.0, h / 2.0, zone)], vec![(CORRIDOR_WIDTH / 2.0, h / 2.0, zone)])
+}
+
+// Octagonal rings of wall around ISLAND_COUNT spots on an ellipse, each missing the side that
+// faces the middle of the world
+fn islands(w: f64, h: f64) -> Layout {
+    let (cx, cy) = (w / 2.0, h / 2.0);
+    let mut walls = Vec::new();
+    let mut zones = Vec::new();
+    for k in 0..ISLAND_COUNT {
+        let a = std::f64::consts::TAU * k as f64 / ISLAND_COUNT as f64;
+        let (ix, iy) = (cx + a.dcos() * (w / 2.0 - ISLAND_RADIUS * 1.5), cy + a.dsin() * (h / 2.0 - ISLAND_RADIUS * 1.5));
+        let corner = |s: usize| {
+            let t = a + std::f64::consts::PI + (s as f64 + 0.5) * std::f64::consts::TAU / 8.0;
+            (ix + t.dcos() * ISLAND_RADIUS, iy + t.dsin() * ISLAND_RADIUS)
+        };
+        // Side 7 (between corners 7 and 0) is the one facing the middle
+        for s in 0..7 { walls.push((corner(s), corner(s + 1))); }
+        zones.push((ix, iy, ISLAND_RADIUS * 0.6));
+    }
+    (walls, zones, vec![(cx, cy, ISLAND_RADIUS)])
+}
+
+fn arena(w: f64, h: f64) -> Layout {
+    let m = ARENA_MARGIN;
+    let corners = [(m, m), (w - m, m), (w - m, h - m), (m, h - m)];
+    let walls = (0..4).map(|k| (corners[k], corners[(k + 1) % 4])).collect();
+    let zone = (w.min(h) - 2.0 * m) * 0.12;
+    let spawns = corners.iter().map(|&(x, y)| (x + (w / 2.0 - x).signum() * zone * 1.5, y + (h / 2.0 - y).signum() * zone * 1.5, zone)).collect();
+    (walls, vec![(w / 2.0, h / 2.0, zone * 1.5)], spawns)
+}
@@ -0,0 +1,46 @@
+use crate::rng::Math;
+use wasm_bindgen::prelude::*;
+
+use crate::Simulation;
+
+// Who may pair up with a chosen parent
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ParentPool {
+    Global,        // Anyone in the population (classic behavior)
+    SameTribeOnly, // Only the first parent's tribe-mates, so tribes stay genetically distinct
+}
+
+impl Simulation {
+    // Two tournament winners that can both afford to reproduce, if any
+    pub(crate) fn select_parents(&self, dead: usize) -> Option<(usize, usize)> {
+        let p1 = self.pick_parent(&[dead], None)?;
+        let p2 = self.pick_parent(&[dead, p1], Some(p1))?;
+        let fit = |p: usize| self.agents.energies[p] > self.reproduction_threshold;
+        if fit(p1) && fit(p2) { Some((p1, p2)) } else { None }
+    }
+
+    // Tournament: best of `tournament_size` random samples, skipping `exclude` and anyone
+    // still cooling down from their last offspring. With `mate_of` set, only candidates that
+    // agent could pair with count: same species under speciation, same tribe if the pool says so.
+    pub(crate) fn pick_parent(&self, exclude: &[usize], mate_of: Option<usize>) -> Option<usize> {
+        let total_agents = self.agents.len();
+        let mut best: Option<(usize, f64)> = None;
+        let mut sampled = 0;
+        for _ in 0..(self.tournament_size * 4) {
+            if sampled == self.tournament_size { break; }
+            let r = (Math::random() * total_agents as f64) as usize;
+            if exclude.contains(&r) || self.agents.vacant[r] || self.agents.breed_ready[r] > self.tick { continue; }
+            if mate_of.is_some_and(|m| !self.compatible(m, r)) { continue; }
+            sampled += 1;
+            let score = self.selection_score(r);
+            if best.is_none_or(|(_, s)| score > s) { best = Some((r, score)); }
+        }
+        best.map(|(idx, _)| idx)
+    }
+
+    fn compatible(&self, a: usize, b: usize) -> bool {
+        if self.speciation && self.agents.species_ids[a] != self.agents.species_ids[b] { return false; }
+        self.parent_pool == ParentPool::Global || self.agents.tribes[a] == self.agents.tribes[b]
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ENERGY_CAP, ENERGY_HISTOGRAM_BINS, TRIBE_COUNT};
+use crate::Simulation;
+
+type Circles = Vec<(f64, f64, f64)>;
+
+// Compact population/terrain summary: enough to compare two moments of a run without a full save
+#[derive(Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub tick: u64,
+    pub metadata: String,
+    pub tribe_counts: Vec<usize>,
+    // Population mean of every brain weight and bias, in genome order
+    pub mean_genome: Vec<f64>,
+    pub mean_traits: Vec<f64>,
+    pub energy_mean: f64,
+    pub energy_std: f64,
+    // Living agents per equal-width energy bin over 0..ENERGY_CAP
+    pub energy_histogram: Vec<usize>,
+    pub rocks: Circles,
+    pub mud: Circles,
+    pub shelters: Circles,
+    pub water: Circles,
+}
+
+#[derive(Serialize)]
+pub struct TerrainDelta {
+    added: usize,
+    removed: usize,
+}
+
+// Everything is "b minus a"
+#[derive(Serialize)]
+pub struct StateDiff {
+    ticks: i64,
+    population: i64,
+    tribes: Vec<i64>,
+    // Mean absolute difference between the two mean genomes
+    genome_drift: f64,
+    traits: Vec<f64>,
+    energy_mean: f64,
+    energy_std: f64,
+    energy_histogram: Vec<i64>,
+    rocks: TerrainDelta,
+    mud: TerrainDelta,
+    shelters: TerrainDelta,
+    water: TerrainDelta,
+}
+
+impl Simulation {
+    pub(crate) fn snapshot(&self) -> StateSnapshot {
+        let living: Vec<usize> = (0..self.agents.len()).filter(|&i| !self.agents.vacant[i]).collect();
+        let n = living.len().max(1) as f64;
+
+        let mut tribe_counts = vec![0; TRIBE_COUNT];
+        let mut mean_genome: Vec<f64> = Vec::new();
+        let mut mean_traits: Vec<f64> = Vec::new();
+        let mut energy_histogram = vec![0; ENERGY_HISTOGRAM_BINS];
+        for &i in &living {
+            tribe_counts[self.agents.tribes[i]] += 1;
+            let b = &self.agents.brains[i];
+            let genome = b.weights_input.iter().chain(&b.weights_output).chain(&b.biases);
+            accumulate(&mut mean_genome, genome.map(|v| v / n));
+            accumulate(&mut mean_traits, self.agents.traits[i].genes().iter().map(|v| v / n));
+            let bin = (self.agents.energies[i] / ENERGY_CAP * ENERGY_HISTOGRAM_BINS as f64) as usize;
+            energy_histogram[bin.min(ENERGY_HISTOGRAM_BINS - 1)] += 1;
+        }
+        let energy_mean = living.iter().map(|&i| self.agents.energies[i]).sum::<f64>() / n;
+        let energy_var = living.iter().map(|&i| (self.agents.energies[i] - energy_mean).powi(2)).sum::<f64>() / n;
+
+        StateSnapshot {
+            tick: self.tick,
+            metadata: self.run_metadata.clone(),
+            tribe_counts, mean_genome, mean_traits,
+            energy_mean, energy_std: energy_var.sqrt(), energy_histogram,
+            rocks: self.rocks.clone(), mud: self.mud.clone(), shelters: self.shelters.clone(), water: self.water.clone(),
+        }
+    }
+}
+
+fn accumulate(sum: &mut Vec<f64>, values: impl Iterator<Item = f64>) {
+    for (k, v) in values.enumerate() {
+        if k == sum.len() { sum.push(0.0); }
+        sum[k] += v;
+    }
+}
+
+pub fn diff(a: &StateSnapshot, b: &StateSnapshot) -> StateDiff {
+    let delta = |x: &[usize], y: &[usize]| -> Vec<i64> {
+        (0..x.len().max(y.len())).map(|k| *y.get(k).unwrap_or(&0) as i64 - *x.get(k).unwrap_or(&0) as i64).collect()
+    };
+    let pairs = a.mean_genome.iter().zip(&b.mean_genome);
+    let genome_drift = pairs.clone().map(|(x, y)| (y - x).abs()).sum::<f64>() / pairs.count().max(1) as f64;
+    StateDiff {
+        ticks: b.tick as i64 - a.tick as i64,
+        population: b.tribe_counts.iter().sum::<usize>() as i64 - a.tribe_counts.iter().sum::<usize>() as i64,
+        tribes: delta(&a.tribe_counts, &b.tribe_counts),
+        genome_drift,
+        traits: a.mean_traits.iter().zip(&b.mean_traits).map(|(x, y)| y - x).collect(),
+        energy_mean: b.energy_mean - a.energy_mean,
+        energy_std: b.energy_std - a.energy_std,
+        energy_histogram: delta(&a.energy_histogram, &b.energy_histogram),
+        rocks: terrain_delta(&a.rocks, &b.rocks),
+        mud: terrain_delta(&a.mud, &b.mud),
+        shelters: terrain_delta(&a.shelters, &b.shelters),
+        water: terrain_delta(&a.water, &b.water),
+    }
+}
+
+fn terrain_delta(a: &Circles, b: &Circles) -> TerrainDelta {
+    TerrainDelta {
+        added: b.iter().filter(|c| !a.contains(c)).count(),
+        removed: a.iter().filter(|c| !b.contains(c)).count(),
+    }
+}
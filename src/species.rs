@@ -0,0 +1,55 @@
+use crate::brain::Brain;
+use std::collections::HashMap;
+
+// Groups genomes into species by compatibility distance to a representative.
+pub struct SpeciesTracker {
+    representatives: Vec<(u32, Brain)>,
+    sizes: HashMap<u32, usize>,
+    next_id: u32,
+    pub threshold: f64,
+}
+
+impl SpeciesTracker {
+    pub fn new(threshold: f64) -> SpeciesTracker {
+        SpeciesTracker { representatives: Vec::new(), sizes: HashMap::new(), next_id: 0, threshold }
+    }
+
+    // First compatible species, or a brand new one represented by this genome
+    pub fn assign(&mut self, brain: &Brain) -> u32 {
+        for (id, rep) in &self.representatives {
+            if brain.distance(rep) < self.threshold {
+                *self.sizes.entry(*id).or_insert(0) += 1;
+                return *id;
+            }
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.representatives.push((id, brain.clone()));
+        self.sizes.insert(id, 1);
+        id
+    }
+
+    // Reassigns every living genome's id in `ids`, drops empty species and re-picks
+    // representatives. Vacant slots aren't members of anything, so their ids are left alone.
+    pub fn respeciate(&mut self, brains: &[Brain], vacant: &[bool], ids: &mut [u32]) {
+        self.sizes.clear();
+        for (i, brain) in brains.iter().enumerate() {
+            if !vacant[i] { ids[i] = self.assign(brain); }
+        }
+
+        let mut new_reps = Vec::new();
+        for (id, _) in &self.representatives {
+            if let Some(member) = (0..ids.len()).find(|&i| !vacant[i] && ids[i] == *id) {
+                new_reps.push((*id, brains[member].clone()));
+            }
+        }
+        self.representatives = new_reps;
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        if let Some(n) = self.sizes.get_mut(&id) { *n = n.saturating_sub(1); }
+    }
+
+    pub fn size(&self, id: u32) -> usize { self.sizes.get(&id).copied().unwrap_or(1).max(1) }
+    pub fn count(&self) -> usize { self.representatives.len() }
+}
@@ -0,0 +1,39 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlImageElement, ImageBitmap};
+
+// A user-supplied image, drawn centered on an entity instead of its vector shape
+pub enum Sprite {
+    Image(HtmlImageElement),
+    Bitmap(ImageBitmap),
+}
+
+impl Sprite {
+    // null/undefined means "no sprite"; anything else must be an <img> or an ImageBitmap
+    pub fn from_js(value: JsValue) -> Result<Option<Sprite>, JsValue> {
+        if value.is_null() || value.is_undefined() { return Ok(None); }
+        let value = match value.dyn_into::<HtmlImageElement>() {
+            Ok(img) => return Ok(Some(Sprite::Image(img))),
+            Err(v) => v,
+        };
+        match value.dyn_into::<ImageBitmap>() {
+            Ok(bitmap) => Ok(Some(Sprite::Bitmap(bitmap))),
+            Err(_) => Err(JsValue::from_str("sprite must be an HTMLImageElement or ImageBitmap")),
+        }
+    }
+
+    pub fn draw(&self, context: &CanvasRenderingContext2d, x: f64, y: f64, size: f64) -> Result<(), JsValue> {
+        let (dx, dy) = (x - size / 2.0, y - size / 2.0);
+        match self {
+            Sprite::Image(img) => context.draw_image_with_html_image_element_and_dw_and_dh(img, dx, dy, size, size),
+            Sprite::Bitmap(bitmap) => context.draw_image_with_image_bitmap_and_dw_and_dh(bitmap, dx, dy, size, size),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Sprites {
+    pub agent: Option<Sprite>,
+    pub predator: Option<Sprite>,
+    pub food: Option<Sprite>,
+}
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use crate::constants::{STATS_HISTORY_TICKS, TRIBE_COUNT};
+use crate::Simulation;
+
+// One tick's headline numbers
+#[derive(Clone, Copy)]
+pub struct TickStats {
+    population: f64,
+    energy: f64,
+    kills: f64,
+    births: f64,
+    thefts: f64,
+    groomings: f64,
+}
+
+#[derive(Serialize)]
+pub struct Summary {
+    mean: f64,
+    min: f64,
+    max: f64,
+    std: f64,
+}
+
+#[derive(Serialize)]
+pub struct RollingStats {
+    // Ticks actually covered (less than asked for early in a run)
+    ticks: usize,
+    population: Summary,
+    energy: Summary,
+    kills: Summary,
+    births: Summary,
+    thefts: Summary,
+    groomings: Summary,
+}
+
+// What's inside one rectangle of the world right now
+#[derive(Serialize)]
+pub struct RegionStats {
+    population: usize,
+    tribe_counts: [usize; TRIBE_COUNT],
+    food: usize,
+    // Food items per 100x100 area
+    food_density: f64,
+    // Over the agents inside; 0 when there are none
+    avg_energy: f64,
+}
+
+// Last STATS_HISTORY_TICKS ticks, plus the running totals needed to turn counters into per-tick values
+#[derive(Default)]
+pub struct StatsHistory {
+    ticks: VecDeque<TickStats>,
+    last_kills: u64,
+    last_births: u64,
+    last_thefts: u64,
+    last_groomings: u64,
+}
+
+impl StatsHistory {
+    pub fn len(&self) -> usize { self.ticks.len() }
+    pub fn bytes(&self) -> usize { self.ticks.capacity() * std::mem::size_of::<TickStats>() }
+}
+
+impl Simulation {
+    pub(crate) fn record_stats(&mut self) {
+        let energy = self.get_avg_energy();
+        let h = &mut self.stats_history;
+        let entry = TickStats {
+            population: self.agents.living() as f64,
+            energy,
+            kills: (self.kills - h.last_kills) as f64,
+            births: (self.births - h.last_births) as f64,
+            thefts: (self.thefts - h.last_thefts) as f64,
+            groomings: (self.groomings - h.last_groomings) as f64,
+        };
+        (h.last_kills, h.last_births, h.last_thefts, h.last_groomings) = (self.kills, self.births, self.thefts, self.groomings);
+        if h.ticks.len() == STATS_HISTORY_TICKS { h.ticks.pop_front(); }
+        h.ticks.push_back(entry);
+    }
+
+    pub(crate) fn rolling_stats(&self, window: usize) -> RollingStats {
+        let h = &self.stats_history.ticks;
+        let recent: Vec<TickStats> = h.iter().skip(h.len().saturating_sub(window)).copied().collect();
+        let summarize = |f: fn(&TickStats) -> f64| {
+            let n = recent.len().max(1) as f64;
+            let mean = recent.iter().map(f).sum::<f64>() / n;
+            let var = recent.iter().map(|t| (f(t) - mean).powi(2)).sum::<f64>() / n;
+            let (min, max) = recent.iter().map(f).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+            if recent.is_empty() { Summary { mean: 0.0, min: 0.0, max: 0.0, std: 0.0 } } else { Summary { mean, min, max, std: var.sqrt() } }
+        };
+        RollingStats {
+            ticks: recent.len(),
+            population: summarize(|t| t.population),
+            energy: summarize(|t| t.energy),
+            kills: summarize(|t| t.kills),
+            births: summarize(|t| t.births),
+            thefts: summarize(|t| t.thefts),
+            groomings: summarize(|t| t.groomings),
+        }
+    }
+
+    // `w` and `h` may be negative (a rectangle dragged up or left)
+    pub(crate) fn region_stats(&self, x: f64, y: f64, w: f64, h: f64) -> RegionStats {
+        let (x0, x1, y0, y1) = (x.min(x + w), x.max(x + w), y.min(y + h), y.max(y + h));
+        let inside = |(px, py): (f64, f64)| px >= x0 && px <= x1 && py >= y0 && py <= y1;
+        let mut stats = RegionStats { population: 0, tribe_counts: [0; TRIBE_COUNT], food: 0, food_density: 0.0, avg_energy: 0.0 };
+        for i in 0..self.agents.len() {
+            if self.agents.vacant[i] || !inside(self.agents.positions[i]) { continue; }
+            stats.population += 1;
+            stats.tribe_counts[self.agents.tribes[i]] += 1;
+            stats.avg_energy += self.agents.energies[i];
+        }
+        stats.avg_energy /= stats.population.max(1) as f64;
+        stats.food = self.food.iter().filter(|&&f| inside(f)).count();
+        let area = (x1 - x0) * (y1 - y0);
+        if area > 0.0 { stats.food_density = stats.food as f64 / area * 10_000.0; }
+        stats
+    }
+}
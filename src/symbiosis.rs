@@ -0,0 +1,20 @@
+use crate::constants::*;
+use crate::Simulation;
+
+impl Simulation {
+    // A small agent grooming a predator up close is fed for it and left alone by that predator
+    // for a while; the predator gets a little energy back too
+    pub(crate) fn clean_predator(&mut self, i: usize, p: usize, distance: f64) -> bool {
+        if distance >= CLEAN_RADIUS || self.agents.traits[i].body_size > CLEANER_MAX_SIZE { return false; }
+        self.gain_energy(i, CLEAN_ENERGY);
+        self.ledger.rewards += CLEAN_ENERGY;
+        self.predator_energies[p] = (self.predator_energies[p] + CLEAN_PREDATOR_ENERGY).min(ENERGY_CAP);
+        self.agents.tolerated_by[i] = Some((p, self.tick + CLEAN_TRUCE_TICKS));
+        self.groomings += 1;
+        true
+    }
+
+    pub(crate) fn tolerated(&self, i: usize, p: usize) -> bool {
+        self.agents.tolerated_by[i].is_some_and(|(q, until)| q == p && self.tick < until)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::cell_grid::CellGrid;
+use crate::constants::{TERRITORY_MIN_STRENGTH, TRIBE_COUNT};
+
+// Coarse grid of per-tribe scent marks. Strengths are capped at 1.0 and fade every tick.
+pub struct TerritoryMap {
+    pub grid: CellGrid,
+    // Index = y * cols + x
+    cells: Vec<[f64; TRIBE_COUNT]>,
+}
+
+impl TerritoryMap {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> TerritoryMap {
+        let grid = CellGrid::new(width, height, cell_size);
+        TerritoryMap { grid, cells: vec![[0.0; TRIBE_COUNT]; grid.cell_count()] }
+    }
+
+    pub fn deposit(&mut self, x: f64, y: f64, tribe: usize, amount: f64) {
+        if let Some(c) = self.grid.cell_at(x, y) {
+            self.cells[c][tribe] = (self.cells[c][tribe] + amount).min(1.0);
+        }
+    }
+
+    pub fn decay(&mut self, factor: f64) {
+        for cell in &mut self.cells {
+            for s in cell.iter_mut() { *s *= factor; }
+        }
+    }
+
+    // Strongest tribe in a cell, if its mark is strong enough to count as a claim
+    pub fn owner_of_cell(&self, cell: usize) -> Option<(usize, f64)> {
+        let (tribe, strength) = self.cells[cell].iter().enumerate()
+            .fold((0, 0.0), |best, (t, &s)| if s > best.1 { (t, s) } else { best });
+        if strength >= TERRITORY_MIN_STRENGTH { Some((tribe, strength)) } else { None }
+    }
+
+    pub fn owner(&self, x: f64, y: f64) -> Option<usize> {
+        self.grid.cell_at(x, y).and_then(|c| self.owner_of_cell(c)).map(|(t, _)| t)
+    }
+}
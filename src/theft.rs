@@ -0,0 +1,31 @@
+use crate::constants::*;
+use crate::fixed::DetMath;
+use crate::rng::Stream;
+use crate::Simulation;
+
+impl Simulation {
+    // Kleptoparasitism: siphons energy off the nearest touching agent of another tribe, keeping
+    // STEAL_EFFICIENCY of it. The victim may lash out, with odds set by its combat gene.
+    pub(crate) fn steal(&mut self, i: usize, neighbors: &[usize], rng: &mut Stream) {
+        let (x, y) = self.agents.positions[i];
+        let victim = neighbors.iter().copied().find(|&j| {
+            j != i && !self.agents.vacant[j] && self.agents.tribes[j] != self.agents.tribes[i] && !self.burrowed(j)
+                && (self.agents.positions[j].0 - x).dhypot(self.agents.positions[j].1 - y) < STEAL_RADIUS
+        });
+        self.agents.energies[i] -= STEAL_COST;
+        self.ledger.metabolism += STEAL_COST;
+        let Some(j) = victim else { return };
+        let amount = self.agents.energies[j].clamp(0.0, STEAL_AMOUNT);
+        self.agents.energies[j] -= amount;
+        self.gain_energy(i, amount * STEAL_EFFICIENCY);
+        self.ledger.stolen += amount;
+        self.ledger.damage += amount * (1.0 - STEAL_EFFICIENCY);
+        self.agents.theft_counts[i] += 1;
+        self.thefts += 1;
+        if rng.next_f64() < STEAL_RETALIATION_CHANCE * self.agents.traits[j].combat {
+            self.agents.energies[i] -= STEAL_RETALIATION_DAMAGE;
+            self.ledger.damage += STEAL_RETALIATION_DAMAGE;
+            self.retaliations += 1;
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// Colors of the main scene elements in draw(); overlays keep their built-in tints
+pub struct Theme {
+    pub background: String,
+    pub food: String,
+    pub predator: String,
+    pub rock: String,
+    pub mud: String,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            background: "#111".to_string(),
+            food: "#00ff00".to_string(),
+            predator: "#ff0000".to_string(),
+            rock: "#555".to_string(),
+            mud: "#1a2b3c".to_string(),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::constants::BRAIN_OUTPUTS;
+use crate::Simulation;
+
+// Per-tick log of one individual, from track_agent until it dies
+pub struct Track {
+    pub id: u64,
+    pub index: usize,
+    rows: Vec<(u64, f64, f64, f64, [f64; BRAIN_OUTPUTS])>,
+}
+
+impl Track {
+    pub fn new(id: u64, index: usize) -> Track { Track { id, index, rows: Vec::new() } }
+
+    // Position and energy as the agent made its decision that tick, plus the outputs it acted on
+    pub fn record(&mut self, tick: u64, (x, y): (f64, f64), energy: f64, outputs: &[f64; BRAIN_OUTPUTS]) {
+        self.rows.push((tick, x, y, energy, *outputs));
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("tick,x,y,energy,turn,thrust,voice,groom,torpor,grab,burrow,steal\n");
+        for (tick, x, y, energy, outputs) in &self.rows {
+            let outputs: Vec<String> = outputs.iter().map(|o| format!("{:.4}", o)).collect();
+            csv.push_str(&format!("{},{:.2},{:.2},{:.3},{}\n", tick, x, y, energy, outputs.join(",")));
+        }
+        csv
+    }
+}
+
+impl Simulation {
+    // The tracked agent's slot still holds the same individual
+    pub(crate) fn is_tracked(&self, i: usize) -> bool {
+        self.track.as_ref().is_some_and(|t| t.index == i && t.id == self.agents.ids[i])
+    }
+}
@@ -0,0 +1,27 @@
+use crate::cell_grid::CellGrid;
+
+// Coarse grid of path wear in 0..1: footsteps build it up, disuse lets it fade
+pub struct TrailMap {
+    pub grid: CellGrid,
+    // Index = y * cols + x
+    pub cells: Vec<f64>,
+}
+
+impl TrailMap {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> TrailMap {
+        let grid = CellGrid::new(width, height, cell_size);
+        TrailMap { grid, cells: vec![0.0; grid.cell_count()] }
+    }
+
+    pub fn wear(&mut self, x: f64, y: f64, amount: f64) {
+        if let Some(c) = self.grid.cell_at(x, y) { self.cells[c] = (self.cells[c] + amount).min(1.0); }
+    }
+
+    pub fn decay(&mut self, factor: f64) {
+        for w in &mut self.cells { *w *= factor; }
+    }
+
+    pub fn at(&self, x: f64, y: f64) -> f64 {
+        self.grid.cell_at(x, y).map_or(0.0, |c| self.cells[c])
+    }
+}
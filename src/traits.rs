@@ -0,0 +1,73 @@
+use crate::rng::Math;
+use serde::Serialize;
+
+use crate::crossover::{recombine, CrossoverStrategy};
+
+// Heritable scalar genes that live outside the neural network
+#[derive(Clone, Serialize)]
+pub struct Traits {
+    pub body_size: f64,
+    pub combat: f64,
+    // Share of PARENTAL_FEED_RATE handed to a nearby juvenile offspring each tick
+    pub care: f64,
+    // Share of PHOTOSYNTHESIS_RATE earned while sitting still in the open, paid for in top speed
+    pub photosynthesis: f64,
+    // Temperament: how much closer predators and rivals seem than they are (1 = accurate)
+    pub fear: f64,
+    pub aggression: f64,
+    // Vision, hearing, whiskers, smell; a group works while its gene is at least 0.5
+    pub sensors: [f64; 4],
+}
+
+impl Traits {
+    pub fn random() -> Traits {
+        Traits {
+            body_size: 0.75 + Math::random() * 0.5,
+            combat: Math::random(),
+            care: Math::random(),
+            photosynthesis: Math::random() * 0.2,
+            fear: 0.75 + Math::random() * 0.5,
+            aggression: 0.75 + Math::random() * 0.5,
+            sensors: std::array::from_fn(|_| 0.5 + Math::random() * 0.5),
+        }
+    }
+
+    pub fn crossover(&self, partner: &Traits, strategy: CrossoverStrategy) -> Traits {
+        Traits::from_genes(&recombine(&self.genes(), &partner.genes(), strategy))
+    }
+
+    pub fn genes(&self) -> [f64; 10] {
+        let [vision, hearing, whiskers, smell] = self.sensors;
+        [self.body_size, self.combat, self.care, self.photosynthesis, self.fear, self.aggression, vision, hearing, whiskers, smell]
+    }
+
+    pub fn sensor_mask(&self) -> [bool; 4] { self.sensors.map(|g| g >= 0.5) }
+
+    // Missing trailing genes (from older exports) fall back to random values
+    pub fn from_genes(genes: &[f64]) -> Traits {
+        let fallback = Traits::random().genes();
+        let g = |k: usize| genes.get(k).copied().filter(|v| v.is_finite()).unwrap_or(fallback[k]);
+        Traits {
+            body_size: g(0).clamp(0.5, 2.0),
+            combat: g(1).clamp(0.0, 1.0),
+            care: g(2).clamp(0.0, 1.0),
+            photosynthesis: g(3).clamp(0.0, 1.0),
+            fear: g(4).clamp(0.5, 2.0),
+            aggression: g(5).clamp(0.5, 2.0),
+            sensors: std::array::from_fn(|k| g(6 + k).clamp(0.0, 1.0)),
+        }
+    }
+
+    pub fn mutate(&self, rate: f64) -> Traits {
+        let nudge = |v: f64| v + (Math::random() * 2.0 - 1.0) * rate * 0.1;
+        Traits {
+            body_size: nudge(self.body_size).clamp(0.5, 2.0),
+            combat: nudge(self.combat).clamp(0.0, 1.0),
+            care: nudge(self.care).clamp(0.0, 1.0),
+            photosynthesis: nudge(self.photosynthesis).clamp(0.0, 1.0),
+            fear: nudge(self.fear).clamp(0.5, 2.0),
+            aggression: nudge(self.aggression).clamp(0.5, 2.0),
+            sensors: self.sensors.map(|g| nudge(g).clamp(0.0, 1.0)),
+        }
+    }
+}
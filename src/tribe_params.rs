@@ -0,0 +1,41 @@
+// Per-tribe overrides for controlled experiments; the defaults leave a tribe on the global settings
+#[derive(Clone, Copy)]
+pub struct TribeParams {
+    // Replaces the global mutation rate for offspring of this tribe
+    pub mutation_rate: Option<f64>,
+    // Multiplies movement speed
+    pub speed_modifier: f64,
+    // Food and predators further away than this don't register on the senses
+    pub vision_range: f64,
+}
+
+impl Default for TribeParams {
+    fn default() -> TribeParams {
+        TribeParams { mutation_rate: None, speed_modifier: 1.0, vision_range: f64::INFINITY }
+    }
+}
+
+impl TribeParams {
+    pub fn set(&mut self, name: &str, value: f64) -> Result<(), String> {
+        match name {
+            "mutation_rate" => self.mutation_rate = Some(value.max(0.0)),
+            "speed_modifier" => self.speed_modifier = value.max(0.0),
+            "vision_range" => self.vision_range = value.max(0.0),
+            _ => return Err(format!("unknown tribe parameter '{}' (expected mutation_rate, speed_modifier or vision_range)", name)),
+        }
+        Ok(())
+    }
+}
+
+// Temporary "play god" effect on a tribe, active until `until` (exclusive)
+#[derive(Clone, Copy)]
+pub struct Intervention {
+    pub value: f64,
+    pub until: u64,
+}
+
+impl Intervention {
+    pub fn active(slot: &Option<Intervention>, tick: u64) -> Option<f64> {
+        slot.filter(|iv| tick < iv.until).map(|iv| iv.value)
+    }
+}
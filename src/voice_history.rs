@@ -0,0 +1,42 @@
+use std::collections::VecDeque;
+
+use crate::constants::{TRIBE_COUNT, VOICE_SPECTROGRAM_BINS, VOICE_SPECTROGRAM_TICKS};
+use crate::Simulation;
+
+type Frame = [[f32; VOICE_SPECTROGRAM_BINS]; TRIBE_COUNT];
+
+// How loudly each tribe called over the last VOICE_SPECTROGRAM_TICKS ticks: per tick, the share
+// of its members in each voice band (outputs 0..1 split evenly, quietest first). Newest last.
+#[derive(Default)]
+pub struct VoiceHistory {
+    frames: VecDeque<Frame>,
+}
+
+impl VoiceHistory {
+    pub fn len(&self) -> usize { self.frames.len() }
+}
+
+impl Simulation {
+    pub(crate) fn record_voices(&mut self) {
+        let mut frame: Frame = [[0.0; VOICE_SPECTROGRAM_BINS]; TRIBE_COUNT];
+        let mut members = [0usize; TRIBE_COUNT];
+        for i in (0..self.agents.len()).filter(|&i| !self.agents.vacant[i]) {
+            let tribe = self.agents.tribes[i];
+            let band = ((self.agents.voices[i] * VOICE_SPECTROGRAM_BINS as f64) as usize).min(VOICE_SPECTROGRAM_BINS - 1);
+            frame[tribe][band] += 1.0;
+            members[tribe] += 1;
+        }
+        for (bands, n) in frame.iter_mut().zip(members) {
+            bands.iter_mut().for_each(|b| *b /= n.max(1) as f32);
+        }
+        let h = &mut self.voice_history.frames;
+        if h.len() == VOICE_SPECTROGRAM_TICKS { h.pop_front(); }
+        h.push_back(frame);
+    }
+
+    // Row-major, one row of bands per tick, oldest first; empty for a tribe that doesn't exist
+    pub(crate) fn voice_spectrogram(&self, tribe: usize) -> Vec<f32> {
+        if tribe >= TRIBE_COUNT { return Vec::new(); }
+        self.voice_history.frames.iter().flat_map(|f| f[tribe]).collect()
+    }
+}
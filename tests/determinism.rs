@@ -0,0 +1,24 @@
+use life_simulation::Simulation;
+
+// Population, mean energy and the exported champions after `ticks` ticks
+fn run(seed: u64, ticks: u32) -> (Vec<usize>, Vec<f64>, Vec<u8>) {
+    let mut sim = Simulation::with_seed(2000.0, 2000.0, seed, true);
+    let mut population = Vec::new();
+    let mut energy = Vec::new();
+    for _ in 0..ticks {
+        sim.step();
+        population.push(sim.get_population());
+        energy.push(sim.get_avg_energy());
+    }
+    (population, energy, sim.export_champions().to_vec())
+}
+
+#[test]
+fn same_seed_replays_identically() {
+    assert_eq!(run(7, 120), run(7, 120));
+}
+
+#[test]
+fn different_seeds_diverge() {
+    assert_ne!(run(7, 120).1, run(8, 120).1);
+}